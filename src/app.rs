@@ -1,14 +1,37 @@
+mod conditions;
+mod mass_save;
+mod prompt;
+
+use std::collections::VecDeque;
+
+use dice_parser::DiceExpr;
 use ratatui::widgets::TableState;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tui_input::Input;
 
-use crate::{model::Creature, model::Encounter};
+pub use crate::app::{
+    conditions::ConditionPicker,
+    mass_save::MassSave,
+    prompt::{Prompt, PromptKind},
+};
+use crate::{
+    model::{
+        Ability, Attack, Charges, Concentration, Creature, CreatureId, Damage, DamageModifier,
+        DamageOutcome, DamageType, DeathSaveOutcome, Duration, Encounter, EncounterSettings,
+        ExhaustionRules, InitiativeEntry, InitiativeRoll, RechargeAbility, Stats, Status,
+        StatusEffect, TieBreak, TurnBoundary, TurnEvent, roll_d20,
+    },
+    storage,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Panel {
     InitiativeTable,
     Sidebar,
     Editor,
+    Prompt,
+    Conditions,
+    MassSave,
 }
 
 #[derive(Debug, Clone)]
@@ -17,57 +40,559 @@ pub struct App {
     pub current_encounter: Encounter,
     pub current_panel: Panel,
     pub editor_state: EditorState,
+    /// Index into `Setting::ALL` of the setting selected in the sidebar.
+    pub setting_index: usize,
+    /// Prompts waiting for input, the front one is shown.
+    pub prompts: VecDeque<Prompt>,
+    pub condition_picker: ConditionPicker,
+    pub mass_save: MassSave,
+    /// Set while the initiatives of a newly rolled combat are being entered.
+    starting_combat: bool,
+    /// Result of the last action, shown in the status line until the next key press.
+    pub status_message: Option<String>,
+}
+
+/// Encounter settings that can be changed from the sidebar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    TieBreak,
+    SkipDead,
+    SkipDowned,
+    Exhaustion,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 4] = [
+        Setting::TieBreak,
+        Setting::SkipDead,
+        Setting::SkipDowned,
+        Setting::Exhaustion,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Setting::TieBreak => "Tie-break",
+            Setting::SkipDead => "Skip dead",
+            Setting::SkipDowned => "Skip downed players",
+            Setting::Exhaustion => "Exhaustion rules",
+        }
+    }
+
+    /// Return the current value of the setting, formatted for display.
+    pub fn value(&self, settings: &EncounterSettings) -> &'static str {
+        match self {
+            Setting::TieBreak => match settings.tie_break {
+                TieBreak::PlayersFirst => "Players first",
+                TieBreak::Manual => "Manual",
+            },
+            Setting::SkipDead => on_off(settings.skip_dead),
+            Setting::SkipDowned => on_off(settings.skip_downed),
+            Setting::Exhaustion => match settings.exhaustion {
+                ExhaustionRules::Rules2014 => "2014",
+                ExhaustionRules::Rules2024 => "2024",
+            },
+        }
+    }
+
+    pub fn toggle(&self, settings: &mut EncounterSettings) {
+        match self {
+            Setting::TieBreak => {
+                settings.tie_break = match settings.tie_break {
+                    TieBreak::PlayersFirst => TieBreak::Manual,
+                    TieBreak::Manual => TieBreak::PlayersFirst,
+                }
+            }
+            Setting::SkipDead => settings.skip_dead = !settings.skip_dead,
+            Setting::SkipDowned => settings.skip_downed = !settings.skip_downed,
+            Setting::Exhaustion => {
+                settings.exhaustion = match settings.exhaustion {
+                    ExhaustionRules::Rules2014 => ExhaustionRules::Rules2024,
+                    ExhaustionRules::Rules2024 => ExhaustionRules::Rules2014,
+                }
+            }
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "On" } else { "Off" }
+}
+
+/// Which `Creature` variant the editor creates.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum CreatureKind {
+    Player,
+    #[default]
+    Monster,
+    /// A monster that follows the player rules at 0 HP, see `Creature::Monster`.
+    ImportantNpc,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct EditorState {
+    pub kind: CreatureKind,
     pub name_input: Input,
     pub max_hp_input: Input,
     pub cur_hp_input: Input,
     pub ac_input: Input,
     pub cr_input: Input,
+    /// Ability score inputs, indexed by `Ability as usize`.
+    pub stat_inputs: [Input; 6],
+    /// Comma separated abilities, see `Ability::parse_list`.
+    pub saves_input: Input,
+    /// Comma separated damage types, see `DamageType::parse_list`.
+    pub resistances_input: Input,
+    pub vulnerabilities_input: Input,
+    pub immunities_input: Input,
+    pub legendary_actions_input: Input,
+    pub legendary_resistances_input: Input,
+    /// Comma separated abilities, see `RechargeAbility::parse_list`.
+    pub recharge_input: Input,
+    /// Comma separated attacks, see `Attack::parse_list`.
+    pub attacks_input: Input,
+    pub amount_input: Input,
     pub active_input: EditorField,
+    pub errors: Vec<(EditorField, String)>,
+    /// Id of the creature being edited, `None` when creating a new one.
+    pub editing: Option<CreatureId>,
 }
 
 impl EditorState {
+    /// Create an editor pre-filled with the details of `creature`.
+    pub fn from_creature(creature: &Creature) -> Self {
+        let max_input = |charges: Option<&Charges>| {
+            Input::new(charges.map(|c| c.max.to_string()).unwrap_or_default())
+        };
+        let (kind, level_or_cr) = match creature {
+            Creature::Player { level, .. } => (CreatureKind::Player, level.to_string()),
+            Creature::Monster { cr, important, .. } => (
+                if *important {
+                    CreatureKind::ImportantNpc
+                } else {
+                    CreatureKind::Monster
+                },
+                format_cr(*cr),
+            ),
+        };
+        let stats = creature.stats();
+        let props = creature.props();
+
+        EditorState {
+            kind,
+            name_input: Input::new(creature.name().to_string()),
+            max_hp_input: Input::new(creature.max_hp().to_string()),
+            cur_hp_input: Input::new(creature.hp().to_string()),
+            ac_input: Input::new(creature.ac().to_string()),
+            cr_input: Input::new(level_or_cr),
+            stat_inputs: Ability::ALL.map(|a| Input::new(stats.score(a).to_string())),
+            saves_input: Input::new(Ability::format_list(creature.save_proficiencies())),
+            resistances_input: Input::new(DamageType::format_list(&props.resistances)),
+            vulnerabilities_input: Input::new(DamageType::format_list(&props.vulnerabilities)),
+            immunities_input: Input::new(DamageType::format_list(&props.immunities)),
+            legendary_actions_input: max_input(creature.legendary_actions()),
+            legendary_resistances_input: max_input(creature.legendary_resistances()),
+            recharge_input: Input::new(RechargeAbility::format_list(creature.recharge_abilities())),
+            attacks_input: Input::new(Attack::format_list(creature.attacks())),
+            editing: Some(creature.id()),
+            ..Default::default()
+        }
+    }
+
     pub fn next_field(&mut self) {
         self.active_input = match self.active_input {
+            EditorField::Kind => EditorField::Name,
             EditorField::Name => EditorField::CurrentHP,
             EditorField::CurrentHP => EditorField::MaxHP,
             EditorField::MaxHP => EditorField::AC,
             EditorField::AC => EditorField::CR,
-            EditorField::CR => EditorField::Amount,
-            EditorField::Amount | EditorField::Unfocused => EditorField::Name,
+            EditorField::CR => EditorField::Stat(Ability::Strength),
+            EditorField::Stat(ability) => match ability.next() {
+                Some(next) => EditorField::Stat(next),
+                None => EditorField::SaveProficiencies,
+            },
+            EditorField::SaveProficiencies => EditorField::Resistances,
+            EditorField::Resistances => EditorField::Vulnerabilities,
+            EditorField::Vulnerabilities => EditorField::Immunities,
+            EditorField::Immunities => EditorField::LegendaryActions,
+            EditorField::LegendaryActions => EditorField::LegendaryResistances,
+            EditorField::LegendaryResistances => EditorField::Recharge,
+            EditorField::Recharge => EditorField::Attacks,
+            EditorField::Attacks => EditorField::Amount,
+            EditorField::Amount | EditorField::Unfocused => EditorField::Kind,
         };
     }
     pub fn previous_field(&mut self) {
         self.active_input = match self.active_input {
-            EditorField::Name => EditorField::Amount,
+            EditorField::Kind => EditorField::Amount,
+            EditorField::Name => EditorField::Kind,
             EditorField::CurrentHP => EditorField::Name,
             EditorField::MaxHP => EditorField::CurrentHP,
             EditorField::AC => EditorField::MaxHP,
             EditorField::CR => EditorField::AC,
-            EditorField::Amount | EditorField::Unfocused => EditorField::CR,
+            EditorField::Stat(ability) => match ability.previous() {
+                Some(previous) => EditorField::Stat(previous),
+                None => EditorField::CR,
+            },
+            EditorField::SaveProficiencies => EditorField::Stat(Ability::Charisma),
+            EditorField::Resistances => EditorField::SaveProficiencies,
+            EditorField::Vulnerabilities => EditorField::Resistances,
+            EditorField::Immunities => EditorField::Vulnerabilities,
+            EditorField::LegendaryActions => EditorField::Immunities,
+            EditorField::LegendaryResistances => EditorField::LegendaryActions,
+            EditorField::Recharge => EditorField::LegendaryResistances,
+            EditorField::Attacks => EditorField::Recharge,
+            EditorField::Amount | EditorField::Unfocused => EditorField::Attacks,
+        };
+    }
+
+    /// Select the next creature kind, wrapping around after the last.
+    pub fn next_kind(&mut self) {
+        self.kind = match self.kind {
+            CreatureKind::Player => CreatureKind::Monster,
+            CreatureKind::Monster => CreatureKind::ImportantNpc,
+            CreatureKind::ImportantNpc => CreatureKind::Player,
+        };
+    }
+
+    /// Select the previous creature kind, wrapping around before the first.
+    pub fn previous_kind(&mut self) {
+        self.kind = match self.kind {
+            CreatureKind::Player => CreatureKind::ImportantNpc,
+            CreatureKind::Monster => CreatureKind::Player,
+            CreatureKind::ImportantNpc => CreatureKind::Monster,
+        };
+    }
+
+    /// Return the label of the level / challenge rating input for the current kind.
+    pub fn level_or_cr_label(&self) -> &'static str {
+        match self.kind {
+            CreatureKind::Player => "Level",
+            CreatureKind::Monster | CreatureKind::ImportantNpc => "CR",
+        }
+    }
+
+    /// Clear all inputs and errors and focus the first field.
+    pub fn reset(&mut self) {
+        *self = EditorState::default();
+    }
+
+    /// Return the validation error of `field`, if any.
+    pub fn error(&self, field: EditorField) -> Option<&str> {
+        self.errors
+            .iter()
+            .find(|(f, _)| *f == field)
+            .map(|(_, e)| &e[..])
+    }
+
+    /// Parse the inputs into `Amount` creatures, collecting an error message for every invalid
+    /// field. If Max HP is a dice expression it is rolled separately for every creature.
+    pub fn parse(&self) -> Result<Vec<Creature>, Vec<(EditorField, String)>> {
+        let mut errors = Vec::new();
+
+        let name = self.name_input.value().trim();
+        if name.is_empty() {
+            errors.push((EditorField::Name, String::from("Name is required")));
+        }
+
+        let max_hp = match self.max_hp_input.value().trim() {
+            s if s.chars().all(|c| c.is_ascii_digit()) => match s.parse::<u32>() {
+                Ok(0) => {
+                    errors.push((EditorField::MaxHP, String::from("Must be above 0")));
+                    None
+                }
+                Ok(hp) => Some(MaxHp::Fixed(hp)),
+                Err(_) => {
+                    errors.push((EditorField::MaxHP, String::from("Expected a number")));
+                    None
+                }
+            },
+            s => match DiceExpr::parse(s) {
+                Ok(expr) if s.contains('d') => Some(MaxHp::Rolled(expr)),
+                _ => {
+                    errors.push((EditorField::MaxHP, String::from("Expected e.g. 7 or 2d6+2")));
+                    None
+                }
+            },
+        };
+
+        let cur_hp = match self.cur_hp_input.value().trim() {
+            "" => None,
+            _ if matches!(max_hp, Some(MaxHp::Rolled(_))) => {
+                errors.push((
+                    EditorField::CurrentHP,
+                    String::from("Empty when rolling HP"),
+                ));
+                None
+            }
+            s => match s.parse::<u32>() {
+                Ok(hp) if matches!(max_hp, Some(MaxHp::Fixed(max)) if hp > max) => {
+                    errors.push((EditorField::CurrentHP, String::from("Above max HP")));
+                    None
+                }
+                Ok(hp) => Some(hp),
+                Err(_) => {
+                    errors.push((EditorField::CurrentHP, String::from("Expected a number")));
+                    None
+                }
+            },
+        };
+
+        let ac = match self.ac_input.value().trim() {
+            "" => 10,
+            s => s.parse::<u32>().unwrap_or_else(|_| {
+                errors.push((EditorField::AC, String::from("Expected a number")));
+                0
+            }),
+        };
+
+        let (level, cr) = match (self.kind, self.cr_input.value().trim()) {
+            (_, "") => (None, None),
+            (CreatureKind::Player, s) => match s.parse::<u8>() {
+                Ok(level @ 1..=20) => (Some(level), None),
+                _ => {
+                    errors.push((EditorField::CR, String::from("Expected a level 1-20")));
+                    (None, None)
+                }
+            },
+            (CreatureKind::Monster | CreatureKind::ImportantNpc, s) => {
+                let cr = parse_cr(s);
+                if cr.is_none() {
+                    errors.push((EditorField::CR, String::from("Expected e.g. 3, 1/4 or 0.5")));
+                }
+                (None, cr)
+            }
+        };
+
+        let mut scores = [10; 6];
+        for ability in Ability::ALL {
+            let input = self.stat_inputs[ability as usize].value().trim();
+            if input.is_empty() {
+                continue;
+            }
+            match input.parse::<u8>() {
+                Ok(score @ 1..=30) => scores[ability as usize] = score,
+                _ => errors.push((EditorField::Stat(ability), String::from("1-30"))),
+            }
+        }
+        let [str, dex, con, int, wis, cha] = scores;
+        let stats = Stats::new(str, dex, con, int, wis, cha);
+
+        let save_proficiencies =
+            Ability::parse_list(self.saves_input.value()).unwrap_or_else(|e| {
+                errors.push((EditorField::SaveProficiencies, e));
+                Vec::new()
+            });
+
+        let mut parse_defenses = |field: EditorField, input: &Input| {
+            DamageType::parse_list(input.value()).unwrap_or_else(|e| {
+                errors.push((field, e));
+                Vec::new()
+            })
+        };
+        let resistances = parse_defenses(EditorField::Resistances, &self.resistances_input);
+        let vulnerabilities =
+            parse_defenses(EditorField::Vulnerabilities, &self.vulnerabilities_input);
+        let immunities = parse_defenses(EditorField::Immunities, &self.immunities_input);
+
+        let mut parse_legendary = |field: EditorField, input: &Input| match input.value().trim() {
+            "" => 0,
+            _ if self.kind == CreatureKind::Player => {
+                errors.push((field, String::from("Monsters only")));
+                0
+            }
+            s => s.parse::<u8>().unwrap_or_else(|_| {
+                errors.push((field, String::from("Expected a number")));
+                0
+            }),
+        };
+        let legendary_actions =
+            parse_legendary(EditorField::LegendaryActions, &self.legendary_actions_input);
+        let legendary_resistances = parse_legendary(
+            EditorField::LegendaryResistances,
+            &self.legendary_resistances_input,
+        );
+
+        let recharge_abilities = match self.recharge_input.value().trim() {
+            "" => Vec::new(),
+            _ if self.kind == CreatureKind::Player => {
+                errors.push((EditorField::Recharge, String::from("Monsters only")));
+                Vec::new()
+            }
+            s => RechargeAbility::parse_list(s).unwrap_or_else(|e| {
+                errors.push((EditorField::Recharge, e));
+                Vec::new()
+            }),
+        };
+
+        let attacks = Attack::parse_list(self.attacks_input.value()).unwrap_or_else(|e| {
+            errors.push((EditorField::Attacks, e));
+            Vec::new()
+        });
+
+        let amount = match self.amount_input.value().trim() {
+            _ if self.editing.is_some() => 1,
+            "" => 1,
+            s => match s.parse::<u32>() {
+                Ok(amount @ 1..=99) => amount,
+                _ => {
+                    errors.push((EditorField::Amount, String::from("1-99")));
+                    1
+                }
+            },
         };
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut creatures = Vec::new();
+        for _ in 0..amount {
+            let max_hp = match &max_hp {
+                Some(MaxHp::Fixed(hp)) => *hp,
+                Some(MaxHp::Rolled(expr)) => match expr.roll() {
+                    Ok(roll) => roll.total.max(1) as u32,
+                    Err(_) => {
+                        return Err(vec![(
+                            EditorField::MaxHP,
+                            String::from("Could not roll hit dice"),
+                        )]);
+                    }
+                },
+                None => 0,
+            };
+            let mut creature = match self.kind {
+                CreatureKind::Player => {
+                    Creature::new_player(name, max_hp, ac, cur_hp, Some(stats), level)
+                }
+                CreatureKind::Monster | CreatureKind::ImportantNpc => {
+                    Creature::new_monster(name, max_hp, ac, cur_hp, Some(stats), cr)
+                }
+            };
+            creature.set_important(self.kind == CreatureKind::ImportantNpc);
+            creature.set_save_proficiencies(save_proficiencies.clone());
+            creature.set_legendary(legendary_actions, legendary_resistances);
+            creature.set_recharge_abilities(recharge_abilities.clone());
+            creature.set_attacks(attacks.clone());
+            creature.set_damage_defenses(
+                resistances.clone(),
+                vulnerabilities.clone(),
+                immunities.clone(),
+            );
+            creatures.push(creature);
+        }
+        Ok(creatures)
+    }
+}
+
+/// Max HP entered in the editor, either a fixed value or hit dice rolled for every creature.
+enum MaxHp {
+    Fixed(u32),
+    Rolled(DiceExpr),
+}
+
+/// Parse a save DC and ability, e.g. `13 wis`.
+fn parse_save(value: &str) -> Result<(u8, Ability), String> {
+    let error = || String::from("Expected e.g. 13 wis");
+    let (dc, ability) = value.split_once(' ').ok_or_else(error)?;
+    let dc = dc.parse::<u8>().map_err(|_| error())?;
+    Ok((dc, ability.parse().map_err(|_| error())?))
+}
+
+/// Format the current hit points of `creature`, with temporary hit points as e.g. `23+5`.
+pub fn format_hp(creature: &Creature) -> String {
+    match creature.temp_hp() {
+        0 => creature.hp().to_string(),
+        temp => format!("{}+{}", creature.hp(), temp),
+    }
+}
+
+/// Parse an amount of damage or healing, either a number or a dice expression which is rolled.
+fn roll_amount(value: &str) -> Result<u32, String> {
+    if let Ok(amount) = value.parse::<u32>() {
+        return Ok(amount);
+    }
+    DiceExpr::parse(value)
+        .and_then(|expr| expr.roll())
+        .map(|roll| roll.total.max(0) as u32)
+        .map_err(|_| String::from("Expected a number or e.g. 2d6+3"))
+}
+
+/// Parse damage as an amount followed by an optional damage type, e.g. `2d6+3 magical slashing`.
+/// A trailing `crit` marks the damage as a critical hit.
+fn roll_damage(value: &str) -> Result<Damage, String> {
+    let words: Vec<&str> = value.split_whitespace().collect();
+    if let Some((last, rest)) = words.split_last()
+        && last.eq_ignore_ascii_case("crit")
+        && !rest.is_empty()
+    {
+        return roll_damage(&rest.join(" ")).map(Damage::critical);
+    }
+    // Damage type names are at most two words long
+    for type_words in [2, 1] {
+        if words.len() > type_words {
+            let (amount, damage_type) = words.split_at(words.len() - type_words);
+            if let Ok(damage_type) = damage_type.join(" ").parse() {
+                return roll_amount(&amount.join(" ")).map(|a| Damage::typed(a, damage_type));
+            }
+        }
+    }
+    roll_amount(value)
+        .map(Damage::new)
+        .map_err(|_| String::from("Expected e.g. 7 or 2d6+3 fire"))
+}
+
+/// Format a challenge rating the way stat blocks do, e.g. `1/4` or `3`.
+pub fn format_cr(cr: f64) -> String {
+    if cr.fract() <= f64::EPSILON {
+        cr.floor().to_string()
+    } else {
+        match cr {
+            0.125 => String::from("1/8"),
+            0.25 => String::from("1/4"),
+            0.5 => String::from("1/2"),
+            0.75 => String::from("3/4"),
+            _ => cr.to_string(),
+        }
     }
 }
 
+/// Parse a challenge rating written either as a decimal (`0.5`) or a fraction (`1/2`).
+fn parse_cr(s: &str) -> Option<f64> {
+    let cr = match s.split_once('/') {
+        Some((num, den)) => {
+            let den = den.trim().parse::<f64>().ok()?;
+            if den == 0.0 {
+                return None;
+            }
+            num.trim().parse::<f64>().ok()? / den
+        }
+        None => s.parse::<f64>().ok()?,
+    };
+    (cr.is_finite() && cr >= 0.0).then_some(cr)
+}
+
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
 pub enum EditorField {
+    Kind,
     #[default]
     Name,
     MaxHP,
     CurrentHP,
     AC,
     CR,
+    Stat(Ability),
+    SaveProficiencies,
+    Resistances,
+    Vulnerabilities,
+    Immunities,
+    LegendaryActions,
+    LegendaryResistances,
+    Recharge,
+    Attacks,
     Amount,
     Unfocused,
 }
 
-pub struct NumInput {
-    pub value: String,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct SerializableApp {
     current_encounter: Encounter,
@@ -95,8 +620,18 @@ impl From<SerializableApp> for App {
         let mut app = Self {
             main_table_state,
             current_encounter: value.current_encounter,
-            current_panel: value.current_panel,
+            // Pending prompts are not stored, so do not come back to an empty one.
+            current_panel: match value.current_panel {
+                Panel::Prompt | Panel::Conditions | Panel::MassSave => Panel::InitiativeTable,
+                panel => panel,
+            },
             editor_state: EditorState::default(),
+            setting_index: 0,
+            prompts: VecDeque::new(),
+            condition_picker: ConditionPicker::default(),
+            mass_save: MassSave::default(),
+            starting_combat: false,
+            status_message: None,
         };
         app.sync_table_state();
         app
@@ -132,74 +667,2264 @@ impl App {
         self.current_encounter.select_previous_cursor();
     }
 
+    /// Pass the turn on, see `App::report_turn_events`.
     pub fn increment_initiative_order(&mut self) {
-        self.current_encounter.select_next_initiative();
+        let events = self.current_encounter.select_next_initiative();
         self.sync_table_state();
+        self.report_turn_events(events);
     }
 
-    pub fn select_panel(&mut self, panel: Panel) {
-        self.current_panel = panel;
+    /// Announce the status effects that ran out as the turn passed on and ask for the saves and
+    /// legendary actions that are due, and for a death save if it landed on a downed player.
+    fn report_turn_events(&mut self, events: Vec<TurnEvent>) {
+        let mut expired = Vec::new();
+        for event in events {
+            match event {
+                TurnEvent::Expired { creature, status } => {
+                    if let Some(creature) = self.current_encounter.creature(creature) {
+                        expired.push(format!("{} ends on {}", status, creature.name()));
+                    }
+                }
+                TurnEvent::SaveDue {
+                    creature, status, ..
+                } => self.open_prompt(Prompt::new(PromptKind::RepeatSave(creature, status))),
+                TurnEvent::ConcentrationEnded { creature, spell } => {
+                    if let Some(creature) = self.current_encounter.creature(creature) {
+                        expired.push(format!("{} ends for {}", spell, creature.name()));
+                    }
+                }
+                TurnEvent::LegendaryActionsDue { creature, left } => {
+                    self.open_prompt(Prompt::new(PromptKind::LegendaryActions(creature, left)))
+                }
+                TurnEvent::EntryReached { entry } => {
+                    if let Some(entry) = self.current_encounter.entries.get(entry) {
+                        expired.push(entry.label());
+                    }
+                }
+                TurnEvent::RechargeRolled {
+                    creature,
+                    ability,
+                    roll,
+                    recharged,
+                } => {
+                    if let Some(creature) = self.current_encounter.creature(creature) {
+                        expired.push(if recharged {
+                            format!(
+                                "{} rolls {} and recharges {}",
+                                creature.name(),
+                                roll,
+                                ability
+                            )
+                        } else {
+                            format!(
+                                "{} rolls {}, {} stays spent",
+                                creature.name(),
+                                roll,
+                                ability
+                            )
+                        });
+                    }
+                }
+            }
+        }
+        if !expired.is_empty() {
+            self.status_message = Some(expired.join(", "));
+        }
+
+        if let Some(creature) = self
+            .current_encounter
+            .active_creature()
+            .filter(|c| c.needs_death_save())
+        {
+            self.open_prompt(Prompt::new(PromptKind::DeathSave(creature.id())));
+        }
     }
 
-    pub fn submit_editor(&mut self) {
-        todo!();
+    pub fn decrement_initiative_order(&mut self) {
+        self.current_encounter.select_previous_initiative();
+        self.sync_table_state();
     }
-}
 
-impl Serialize for App {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        SerializableApp::from(self).serialize(serializer)
+    /// Order the encounter by initiative, see `Encounter::sort_by_initiative`.
+    pub fn sort_initiative(&mut self) {
+        self.current_encounter.sort_by_initiative();
+        self.sync_table_state();
     }
-}
 
-impl<'de> Deserialize<'de> for App {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        SerializableApp::deserialize(deserializer).map(Into::into)
+    /// Select the next setting in the sidebar
+    pub fn select_next_setting(&mut self) {
+        self.setting_index = (self.setting_index + 1) % Setting::ALL.len();
     }
-}
 
-impl Default for App {
-    fn default() -> Self {
-        let mut app = App {
-            main_table_state: TableState::default(),
-            current_encounter: Encounter::default(),
-            current_panel: Panel::InitiativeTable,
-            editor_state: EditorState::default(),
+    /// Select the previous setting in the sidebar
+    pub fn select_previous_setting(&mut self) {
+        self.setting_index = self
+            .setting_index
+            .checked_sub(1)
+            .unwrap_or(Setting::ALL.len() - 1);
+    }
+
+    /// Toggle the setting selected in the sidebar. Changing the tie-break re-sorts the encounter.
+    pub fn toggle_selected_setting(&mut self) {
+        let setting = Setting::ALL[self.setting_index];
+        setting.toggle(&mut self.current_encounter.settings);
+        if setting == Setting::TieBreak {
+            self.sort_initiative();
+        }
+    }
+
+    /// Roll initiative for the creatures selected by `mode`. When only monsters are rolled, every
+    /// player is prompted for the initiative they rolled themselves. Once all initiatives are in
+    /// the encounter is sorted and restarts at the top of round 1.
+    pub fn roll_initiative(&mut self, mode: InitiativeRoll) {
+        self.current_encounter.roll_initiative(mode);
+        if mode == InitiativeRoll::MonstersOnly {
+            for creature in &self.current_encounter.creatures {
+                if let Creature::Player { .. } = creature {
+                    let prompt = Prompt::new(PromptKind::Initiative(creature.id()));
+                    self.prompts.push_back(match creature.get_initiative() {
+                        Some(init) => prompt.with_value(init.to_string()),
+                        None => prompt,
+                    });
+                }
+            }
+        }
+
+        self.starting_combat = true;
+        if self.prompts.is_empty() {
+            self.start_combat();
+        } else {
+            self.current_panel = Panel::Prompt;
+        }
+    }
+
+    fn start_combat(&mut self) {
+        self.starting_combat = false;
+        self.current_encounter.sort_by_initiative();
+        self.current_encounter.restart_rounds();
+        self.sync_table_state();
+    }
+
+    /// Prompt for the initiative of the creature under the cursor.
+    pub fn enter_selected_initiative(&mut self) {
+        let index = self.current_encounter.cursor_index;
+        if let Some(creature) = self.current_encounter.creatures.get(index) {
+            let prompt = Prompt::new(PromptKind::Initiative(creature.id()));
+            self.open_prompt(match creature.get_initiative() {
+                Some(init) => prompt.with_value(init.to_string()),
+                None => prompt,
+            });
+        }
+    }
+
+    /// Prompt for damage dealt to the creature under the cursor.
+    pub fn damage_selected_creature(&mut self) {
+        if let Some(id) = self.selected_id() {
+            self.open_prompt(Prompt::new(PromptKind::Damage(id)));
+        }
+    }
+
+    /// Prompt for healing received by the creature under the cursor.
+    pub fn heal_selected_creature(&mut self) {
+        if let Some(id) = self.selected_id() {
+            self.open_prompt(Prompt::new(PromptKind::Heal(id)));
+        }
+    }
+
+    /// Prompt for temporary hit points granted to the creature under the cursor.
+    pub fn grant_selected_temp_hp(&mut self) {
+        if let Some(id) = self.selected_id() {
+            self.open_prompt(Prompt::new(PromptKind::TempHp(id)));
+        }
+    }
+
+    /// Grant `amount` temporary hit points to the creature at `index`, or remove them if `amount`
+    /// is 0.
+    pub fn grant_temp_hp(&mut self, index: usize, amount: u32) {
+        let Some(creature) = self.current_encounter.creatures.get_mut(index) else {
+            return;
         };
-        app.sync_table_state();
-        app
+        if amount == 0 {
+            creature.clear_temp_hp();
+            self.status_message = Some(format!("{} loses all temp HP", creature.name()));
+        } else {
+            creature.grant_temp_hp(amount);
+            self.status_message = Some(format!(
+                "{} has {} temp HP",
+                creature.name(),
+                creature.temp_hp()
+            ));
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{App, Panel};
-    use crate::model::Creature;
+    /// Deal `damage` to the creature at `index` and report what it took, and why, in the status
+    /// line. A creature that keeps concentrating through the damage is asked for a Constitution
+    /// save.
+    pub fn damage_creature(&mut self, index: usize, damage: Damage) {
+        let Some(creature) = self.current_encounter.creatures.get_mut(index) else {
+            return;
+        };
+        let report = creature.take_damage(damage);
+        let name = creature.name();
 
-    #[test]
-    fn app_serde_round_trips_encounter_state() {
-        let mut app = App::default();
-        app.select_panel(Panel::Editor);
-        app.current_encounter
-            .add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
-        app.current_encounter
-            .add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
-        app.current_encounter.initiative_index = 1;
-        app.sync_table_state();
+        let kind = match damage.damage_type {
+            Some(damage_type) => format!("{} damage", damage_type),
+            None => String::from("damage"),
+        };
+        let reason = match report.modifier {
+            DamageModifier::Normal => String::new(),
+            DamageModifier::Immune => String::from(" (immune)"),
+            DamageModifier::Resisted => format!(" (resisted {})", report.dealt),
+            DamageModifier::Vulnerable => format!(" (vulnerable to {})", report.dealt),
+            DamageModifier::ResistedAndVulnerable => {
+                format!(" (resisted and vulnerable to {})", report.dealt)
+            }
+        };
+        let outcome = match report.outcome {
+            DamageOutcome::Survived => {
+                format!("survives with {} HP", format_hp(creature))
+            }
+            DamageOutcome::Downed => match creature.death_saves() {
+                Some(saves) if saves.failures > 0 => {
+                    format!("is downed with {}/3 failed death saves", saves.failures)
+                }
+                _ => String::from("is downed"),
+            },
+            DamageOutcome::Died => String::from("dies"),
+        };
+        self.status_message = Some(format!(
+            "{} takes {} {}{} and {}",
+            name, report.applied, kind, reason, outcome
+        ));
+        self.end_lost_effects();
 
-        let json = serde_json::to_string(&app).unwrap();
-        let mut restored: App = serde_json::from_str(&json).unwrap();
-        restored.sync_table_state();
+        let creature = &self.current_encounter.creatures[index];
+        if report.applied > 0 && creature.concentration().is_some() {
+            let dc = Concentration::save_dc(report.applied);
+            self.open_prompt(Prompt::new(PromptKind::ConcentrationSave(
+                creature.id(),
+                dc,
+            )));
+        }
+    }
 
-        assert_eq!(restored.current_encounter.initiative_index, 1);
-        assert_eq!(restored.main_table_state.selected(), Some(1));
-        assert_eq!(restored.current_encounter.creatures.len(), 2);
-        assert_eq!(restored.current_panel, Panel::Editor);
+    /// Heal the creature at `index` by `amount` and report it in the status line.
+    pub fn heal_creature(&mut self, index: usize, amount: u32) {
+        let Some(creature) = self.current_encounter.creatures.get_mut(index) else {
+            return;
+        };
+        if creature.is_dead() {
+            self.status_message = Some(format!("{} is dead and cannot be healed", creature.name()));
+            return;
+        }
+        let healed = creature.heal(amount);
+        self.status_message = Some(format!(
+            "{} heals {} and is at {}/{} HP",
+            creature.name(),
+            healed,
+            creature.hp(),
+            creature.max_hp()
+        ));
+    }
+
+    /// End the grapples whose grappler can no longer hold on and the concentration of creatures
+    /// who can no longer keep it, see `Encounter::end_broken_grapples` and
+    /// `Encounter::end_broken_concentration`, and report them in the status line.
+    fn end_lost_effects(&mut self) {
+        let mut messages = Vec::new();
+        for (i, concentration) in self.current_encounter.end_broken_concentration() {
+            let name = self.current_encounter.creatures[i].name();
+            messages.push(format!(
+                "{} loses concentration on {}",
+                name, concentration.spell
+            ));
+        }
+        for i in self.current_encounter.end_broken_grapples() {
+            let name = self.current_encounter.creatures[i].name();
+            messages.push(format!("{} is no longer grappled", name));
+        }
+        if !messages.is_empty() {
+            messages.splice(0..0, self.status_message.take());
+            self.status_message = Some(messages.join(", "));
+        }
+    }
+
+    /// Mark the reaction of the creature under the cursor as taken, or as not taken if it was.
+    pub fn toggle_selected_reaction(&mut self) {
+        let index = self.current_encounter.cursor_index;
+        if let Some(creature) = self.current_encounter.creatures.get_mut(index) {
+            let used = creature.toggle_reaction();
+            self.status_message = Some(format!(
+                "{} {} the reaction",
+                creature.name(),
+                if used { "takes" } else { "regains" }
+            ));
+        }
+    }
+
+    /// Mark the bonus action of the creature whose turn it is as taken, or as not taken if it
+    /// was.
+    pub fn toggle_active_bonus_action(&mut self) {
+        let encounter = &mut self.current_encounter;
+        let Some(creature) = encounter
+            .active_index()
+            .and_then(|i| encounter.creatures.get_mut(i))
+        else {
+            self.status_message = Some(String::from("Nobody has a turn"));
+            return;
+        };
+        let used = creature.toggle_bonus_action();
+        self.status_message = Some(format!(
+            "{} {} the bonus action",
+            creature.name(),
+            if used { "takes" } else { "regains" }
+        ));
+    }
+
+    /// Prompt for the spell the creature under the cursor concentrates on.
+    pub fn concentrate_selected_creature(&mut self) {
+        let index = self.current_encounter.cursor_index;
+        if let Some(creature) = self.current_encounter.creatures.get(index) {
+            let spell = creature
+                .concentration()
+                .map(|c| c.spell.clone())
+                .unwrap_or_default();
+            self.open_prompt(
+                Prompt::new(PromptKind::Concentration(creature.id())).with_value(spell),
+            );
+        }
+    }
+
+    /// Make the creature at `index` concentrate on the spell in `value`, for the number of rounds
+    /// after its name if there is one, or end its concentration if `value` is empty.
+    fn submit_concentration(&mut self, index: usize, value: &str) -> Result<(), String> {
+        let Some(name) = self
+            .current_encounter
+            .creatures
+            .get(index)
+            .map(|c| c.name().to_string())
+        else {
+            return Ok(());
+        };
+        if value.is_empty() {
+            if let Some(ended) = self.current_encounter.end_concentration(index) {
+                self.status_message =
+                    Some(format!("{} stops concentrating on {}", name, ended.spell));
+            }
+            return Ok(());
+        }
+
+        let concentration = match value.rsplit_once(' ').map(|(s, r)| (s, r.parse::<u32>())) {
+            Some((_, Ok(0))) => return Err(String::from("A spell lasts at least 1 round")),
+            Some((spell, Ok(rounds))) => {
+                Concentration::new(spell.trim()).with_duration(Duration::Rounds(rounds))
+            }
+            _ => Concentration::new(value),
+        };
+        let spell = concentration.spell.clone();
+        let message = match self
+            .current_encounter
+            .start_concentration(index, concentration)
+        {
+            Some(ended) if ended.spell != spell => format!(
+                "{} stops concentrating on {} and concentrates on {}",
+                name, ended.spell, spell
+            ),
+            _ => format!("{} concentrates on {}", name, spell),
+        };
+        self.status_message = Some(message);
+        Ok(())
+    }
+
+    /// Record the Constitution save of the creature at `index` to keep concentrating against
+    /// `dc`, rolling it if `value` is empty. A rolled save includes the save proficiency and the
+    /// exhaustion penalty of the 2024 rules. The spell ends on a failure.
+    fn submit_concentration_save(
+        &mut self,
+        index: usize,
+        dc: u32,
+        value: &str,
+    ) -> Result<(), String> {
+        let rules = self.current_encounter.settings.exhaustion;
+        let Some(creature) = self.current_encounter.creatures.get(index) else {
+            return Ok(());
+        };
+        let Some(spell) = creature.concentration().map(|c| c.spell.clone()) else {
+            return Ok(());
+        };
+
+        let total = if value.is_empty() {
+            i32::from(roll_d20())
+                + i32::from(creature.save_modifier(Ability::Constitution))
+                + rules.d20_penalty(creature.exhaustion())
+        } else {
+            value
+                .parse::<i32>()
+                .map_err(|_| String::from("Expected the save total"))?
+        };
+        let name = creature.name().to_string();
+        let result = if total >= dc as i32 {
+            format!("keeps concentrating on {}", spell)
+        } else {
+            self.current_encounter.end_concentration(index);
+            format!("loses concentration on {}", spell)
+        };
+        self.status_message = Some(format!(
+            "{} rolls {} on a DC {} CON save and {}",
+            name, total, dc, result
+        ));
+        Ok(())
+    }
+
+    /// Return the id of the creature under the cursor.
+    fn selected_id(&self) -> Option<CreatureId> {
+        let encounter = &self.current_encounter;
+        encounter
+            .creatures
+            .get(encounter.cursor_index)
+            .map(Creature::id)
+    }
+
+    /// Open the condition picker for the creature under the cursor.
+    pub fn open_condition_picker(&mut self) {
+        let encounter = &self.current_encounter;
+        if let Some(creature) = encounter.creatures.get(encounter.cursor_index) {
+            self.condition_picker = ConditionPicker::new(encounter, creature.id());
+            self.current_panel = Panel::Conditions;
+        }
+    }
+
+    /// Add the condition highlighted in the picker, or remove it if the creature has it already.
+    /// `Grappled` is added with the grappler offered by the picker.
+    pub fn toggle_selected_condition(&mut self) {
+        let picker = self.condition_picker;
+        let Some(creature) = self.current_encounter.creature_mut(picker.creature) else {
+            return;
+        };
+
+        match (
+            creature.find_condition(picker.condition()),
+            picker.condition(),
+        ) {
+            (Some(existing), _) => creature.remove_status(existing),
+            (None, Status::Grappled(_)) => match picker.grappler {
+                Some(id) => creature.add_status(Status::Grappled(id)),
+                None => {
+                    self.status_message = Some(String::from("There is nobody to grapple with"));
+                }
+            },
+            (None, condition) => creature.add_status(condition),
+        }
+        self.end_lost_effects();
+    }
+
+    /// Make the condition highlighted in the picker end with the concentration of the creature
+    /// whose turn it is.
+    pub fn link_selected_condition(&mut self) {
+        let Some(status) = self.selected_condition() else {
+            return;
+        };
+        let Some(caster) = self.current_encounter.active_index() else {
+            self.status_message = Some(String::from("Nobody has a turn"));
+            return;
+        };
+        let Some(target) = self
+            .current_encounter
+            .index_of(self.condition_picker.creature)
+        else {
+            return;
+        };
+        let linked = self
+            .current_encounter
+            .link_to_concentration(caster, target, status);
+
+        let encounter = &self.current_encounter;
+        let caster_name = encounter.creatures.get(caster).map_or("?", |c| c.name());
+        self.status_message = Some(if linked {
+            format!(
+                "{} on {} ends with the concentration of {}",
+                status,
+                encounter.creatures[target].name(),
+                caster_name
+            )
+        } else {
+            format!("{} is not concentrating", caster_name)
+        });
+    }
+
+    /// Raise (`step` 1) or lower (`step` -1) the exhaustion level of the creature in the picker
+    /// when `Exhaustion` is highlighted, level 0 removes it. With `Grappled` highlighted the next
+    /// or previous creature is offered as the grappler instead, and becomes the grappler if the
+    /// creature is grappled already.
+    pub fn adjust_selected_condition(&mut self, step: isize) {
+        let picker = &mut self.condition_picker;
+        let Some(creature) = self.current_encounter.creature_mut(picker.creature) else {
+            return;
+        };
+        let current = creature.find_condition(picker.condition());
+
+        match picker.condition() {
+            Status::Exhaustion(_) => {
+                let level = (creature.exhaustion() as isize + step).clamp(0, 6) as u8;
+                creature.set_exhaustion(level);
+                self.end_lost_effects();
+            }
+            Status::Grappled(_) => {
+                picker.cycle_grappler(&self.current_encounter, step);
+                if let (Some(_), Some(grappler)) = (current, picker.grappler)
+                    && let Some(creature) = self.current_encounter.creature_mut(picker.creature)
+                {
+                    creature.add_status(Status::Grappled(grappler));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Prompt for how long the condition highlighted in the picker lasts.
+    pub fn edit_selected_condition_duration(&mut self) {
+        if let Some(status) = self.selected_condition() {
+            let creature = self.condition_picker.creature;
+            self.open_prompt(Prompt::new(PromptKind::ConditionDuration(creature, status)));
+        }
+    }
+
+    /// Prompt for the save that ends the condition highlighted in the picker.
+    pub fn edit_selected_condition_save(&mut self) {
+        if let Some(status) = self.selected_condition() {
+            let creature = self.condition_picker.creature;
+            self.open_prompt(Prompt::new(PromptKind::ConditionSave(creature, status)));
+        }
+    }
+
+    /// Return the status the creature in the picker has of the highlighted condition.
+    fn selected_condition(&self) -> Option<Status> {
+        let picker = self.condition_picker;
+        self.current_encounter
+            .creature(picker.creature)?
+            .find_condition(picker.condition())
+    }
+
+    /// Set how long `status` lasts on the creature at `index`. `value` is a number of rounds,
+    /// `start` or `end` for until the start or end of the next turn of the creature whose turn it
+    /// is, or empty to last until removed.
+    fn submit_condition_duration(
+        &mut self,
+        index: usize,
+        status: Status,
+        value: &str,
+    ) -> Result<(), String> {
+        let Some(effect) = self.status_effect(index, status) else {
+            return Ok(());
+        };
+        let turn = |boundary| {
+            self.current_encounter
+                .active_creature()
+                .map(|c| Duration::UntilTurn {
+                    creature: c.id(),
+                    boundary,
+                })
+                .ok_or_else(|| String::from("Nobody has a turn yet"))
+        };
+        let duration = match &value.to_lowercase()[..] {
+            "" => Duration::Indefinite,
+            "start" => turn(TurnBoundary::Start)?,
+            "end" => turn(TurnBoundary::End)?,
+            rounds => match rounds.parse::<u32>() {
+                Ok(rounds @ 1..) => Duration::Rounds(rounds),
+                _ => return Err(String::from("Expected rounds, start, end or nothing")),
+            },
+        };
+        self.current_encounter
+            .add_status_effect(index, effect.with_duration(duration));
+        Ok(())
+    }
+
+    /// Set the save that ends `status` on the creature at `index`, e.g. `13 wis`, or remove it if
+    /// `value` is empty.
+    fn submit_condition_save(
+        &mut self,
+        index: usize,
+        status: Status,
+        value: &str,
+    ) -> Result<(), String> {
+        let Some(mut effect) = self.status_effect(index, status) else {
+            return Ok(());
+        };
+        effect.save = None;
+        if !value.is_empty() {
+            let (dc, ability) = parse_save(value)?;
+            effect = effect.with_save(dc, ability);
+        }
+        if let Some(creature) = self.current_encounter.creatures.get_mut(index) {
+            creature.add_status_effect(effect);
+        }
+        Ok(())
+    }
+
+    /// Return the effect of `status` on the creature at `index`.
+    fn status_effect(&self, index: usize, status: Status) -> Option<StatusEffect> {
+        self.current_encounter
+            .creatures
+            .get(index)?
+            .status_effects()
+            .iter()
+            .find(|e| e.status == status)
+            .copied()
+    }
+
+    /// Open the mass save popup with the cursor on the creature under the table cursor, and ask
+    /// for the save to make.
+    pub fn open_mass_save(&mut self) {
+        let previous = std::mem::replace(
+            &mut self.mass_save,
+            MassSave::new(self.current_encounter.cursor_index),
+        );
+        self.mass_save.dc = previous.dc;
+        self.mass_save.ability = previous.ability;
+        self.mass_save.half_on_success = previous.half_on_success;
+        self.current_panel = Panel::MassSave;
+        self.edit_mass_save();
+    }
+
+    /// Prompt for the DC and ability of the mass save.
+    pub fn edit_mass_save(&mut self) {
+        let save = format!(
+            "{} {}",
+            self.mass_save.dc,
+            self.mass_save.ability.abbreviation().to_lowercase()
+        );
+        self.open_prompt(Prompt::new(PromptKind::MassSave).with_value(save));
+    }
+
+    /// Roll the mass save for every selected creature that is alive, with their save
+    /// proficiencies and the exhaustion penalty of the 2024 rules, and report how many failed.
+    pub fn roll_mass_save(&mut self) {
+        let rules = self.current_encounter.settings.exhaustion;
+        let save = &mut self.mass_save;
+        save.rolls.clear();
+        for creature in &self.current_encounter.creatures {
+            if !creature.is_alive() || !save.selected.contains(&creature.id()) {
+                continue;
+            }
+            let total = i32::from(roll_d20())
+                + i32::from(creature.save_modifier(save.ability))
+                + rules.d20_penalty(creature.exhaustion());
+            save.rolls
+                .push((creature.id(), total, total >= i32::from(save.dc)));
+        }
+
+        let failed = save.rolls.iter().filter(|(.., passed)| !passed).count();
+        self.status_message = Some(format!(
+            "{} of {} fail the DC {} {} save",
+            failed,
+            save.rolls.len(),
+            save.dc,
+            save.ability.abbreviation()
+        ));
+    }
+
+    /// Prompt for the damage dealt by the effect the mass save was made against.
+    pub fn open_mass_save_damage(&mut self) {
+        if !self.mass_save.rolls.is_empty() {
+            self.open_prompt(Prompt::new(PromptKind::MassSaveDamage));
+        }
+    }
+
+    /// Deal `damage` to every creature that failed the mass save and half of it or none to every
+    /// creature that passed, and report what each of them took.
+    fn damage_mass_save(&mut self, damage: Damage) {
+        let mut messages = Vec::new();
+        for (id, _, passed) in self.mass_save.rolls.clone() {
+            let Some(i) = self.current_encounter.index_of(id) else {
+                continue;
+            };
+            let amount = match (passed, self.mass_save.half_on_success) {
+                (false, _) => damage.amount,
+                (true, true) => damage.amount / 2,
+                (true, false) => continue,
+            };
+            self.damage_creature(i, Damage { amount, ..damage });
+            messages.extend(self.status_message.take());
+        }
+        self.status_message = Some(if messages.is_empty() {
+            String::from("Nobody takes damage")
+        } else {
+            messages.join(", ")
+        });
+    }
+
+    /// Prompt for the legendary actions the creature under the cursor spends.
+    pub fn spend_selected_legendary_actions(&mut self) {
+        let index = self.current_encounter.cursor_index;
+        let Some(creature) = self.current_encounter.creatures.get(index) else {
+            return;
+        };
+        match creature.legendary_actions() {
+            None => {
+                self.status_message = Some(format!("{} has no legendary actions", creature.name()));
+            }
+            Some(_) if self.current_encounter.active_index() == Some(index) => {
+                self.status_message = Some(format!(
+                    "{} cannot take legendary actions on its own turn",
+                    creature.name()
+                ));
+            }
+            Some(actions) => {
+                let kind = PromptKind::LegendaryActions(creature.id(), actions.left);
+                self.open_prompt(Prompt::new(kind));
+            }
+        }
+    }
+
+    /// Prompt for the legendary resistances of the creature under the cursor.
+    pub fn use_selected_legendary_resistance(&mut self) {
+        let index = self.current_encounter.cursor_index;
+        let Some(creature) = self.current_encounter.creatures.get(index) else {
+            return;
+        };
+        if creature.legendary_resistances().is_some() {
+            self.open_prompt(Prompt::new(PromptKind::LegendaryResistances(creature.id())));
+        } else {
+            self.status_message = Some(format!("{} has no legendary resistances", creature.name()));
+        }
+    }
+
+    /// Spend the number of legendary actions in `value` for the creature at `index` out of the
+    /// `left` it had when asked, or none if it is empty. Once its own turn has started the
+    /// creature has regained the actions it spends at the end of the turn before, so they are
+    /// checked against `left` but not taken from the new ones.
+    fn submit_legendary_actions(
+        &mut self,
+        index: usize,
+        left: u8,
+        value: &str,
+    ) -> Result<(), String> {
+        if value.is_empty() {
+            return Ok(());
+        }
+        let cost = match value.parse::<u8>() {
+            Ok(cost @ 1..) => cost,
+            _ => return Err(String::from("Expected the number of actions to spend")),
+        };
+        if cost > left {
+            return Err(format!("Only {} left", left));
+        }
+        let own_turn = self.current_encounter.active_index() == Some(index);
+        let Some(creature) = self.current_encounter.creatures.get_mut(index) else {
+            return Ok(());
+        };
+        let name = creature.name().to_string();
+        let Some(actions) = creature.legendary_actions_mut() else {
+            return Ok(());
+        };
+        if !own_turn && !actions.spend(cost) {
+            return Err(format!("Only {} left", actions.left));
+        }
+        self.status_message = Some(format!(
+            "{} spends {} legendary action{} ({}/{} left)",
+            name,
+            cost,
+            if cost == 1 { "" } else { "s" },
+            actions.left,
+            actions.max
+        ));
+        Ok(())
+    }
+
+    /// Use a legendary resistance of the creature at `index` if `value` is empty, or set the
+    /// number it has left, e.g. back to all of them after a long rest.
+    fn submit_legendary_resistances(&mut self, index: usize, value: &str) -> Result<(), String> {
+        let Some(creature) = self.current_encounter.creatures.get_mut(index) else {
+            return Ok(());
+        };
+        let name = creature.name().to_string();
+        let Some(resistances) = creature.legendary_resistances_mut() else {
+            return Ok(());
+        };
+        let message = if value.is_empty() {
+            if !resistances.spend(1) {
+                return Err(String::from("No legendary resistances left"));
+            }
+            format!(
+                "{} uses a legendary resistance ({}/{} left)",
+                name, resistances.left, resistances.max
+            )
+        } else {
+            match value.parse::<u8>() {
+                Ok(left) if left <= resistances.max => resistances.left = left,
+                _ => return Err(format!("Expected 0-{}", resistances.max)),
+            }
+            format!(
+                "{} has {}/{} legendary resistances left",
+                name, resistances.left, resistances.max
+            )
+        };
+        self.status_message = Some(message);
+        Ok(())
+    }
+
+    /// Prompt for the recharge ability the creature under the cursor uses, suggesting the first
+    /// one it has available.
+    pub fn use_selected_recharge_ability(&mut self) {
+        let index = self.current_encounter.cursor_index;
+        let Some(creature) = self.current_encounter.creatures.get(index) else {
+            return;
+        };
+        let abilities = creature.recharge_abilities();
+        if abilities.is_empty() {
+            self.status_message = Some(format!("{} has no recharge abilities", creature.name()));
+            return;
+        }
+        let suggested = abilities
+            .iter()
+            .find(|a| a.available)
+            .map(|a| a.name.clone())
+            .unwrap_or_default();
+        self.open_prompt(
+            Prompt::new(PromptKind::RechargeAbility(creature.id())).with_value(suggested),
+        );
+    }
+
+    /// Spend the recharge ability named `value` of the creature at `index`, nothing if it is
+    /// empty.
+    fn submit_recharge_ability(&mut self, index: usize, value: &str) -> Result<(), String> {
+        if value.is_empty() {
+            return Ok(());
+        }
+        let Some(creature) = self.current_encounter.creatures.get_mut(index) else {
+            return Ok(());
+        };
+        let name = creature.name().to_string();
+        let ability = creature
+            .recharge_abilities_mut()
+            .iter_mut()
+            .find(|a| a.name.eq_ignore_ascii_case(value))
+            .ok_or_else(|| format!("{} has no {}", name, value))?;
+        if !ability.available {
+            return Err(format!("{} is not recharged", ability.name));
+        }
+        ability.available = false;
+        self.status_message = Some(format!(
+            "{} uses {} (recharge {})",
+            name,
+            ability.name,
+            ability.range()
+        ));
+        Ok(())
+    }
+
+    /// Prompt for the attack the creature whose turn it is makes against the creature under the
+    /// cursor, suggesting its first attack.
+    pub fn attack_selected_creature(&mut self) {
+        let encounter = &self.current_encounter;
+        let Some(attacker) = encounter.active_creature() else {
+            self.status_message = Some(String::from("Nobody has a turn"));
+            return;
+        };
+        let Some(first) = attacker.attacks().first() else {
+            self.status_message = Some(format!("{} has no attacks", attacker.name()));
+            return;
+        };
+        let Some(target) = encounter.creatures.get(encounter.cursor_index) else {
+            return;
+        };
+        let kind = PromptKind::Attack(attacker.id(), target.id());
+        let prompt = Prompt::new(kind).with_value(first.name.clone());
+        self.open_prompt(prompt);
+    }
+
+    /// Roll the attack named `value` of the creature at `attacker` against the creature at
+    /// `target`, nothing if it is empty.
+    fn submit_attack(&mut self, attacker: usize, target: usize, value: &str) -> Result<(), String> {
+        if value.is_empty() {
+            return Ok(());
+        }
+        let Some(creature) = self.current_encounter.creatures.get(attacker) else {
+            return Ok(());
+        };
+        let attack = creature
+            .attacks()
+            .iter()
+            .find(|a| a.name.eq_ignore_ascii_case(value))
+            .cloned()
+            .ok_or_else(|| format!("{} has no {}", creature.name(), value))?;
+        self.resolve_attack(attacker, target, &attack, roll_d20())
+    }
+
+    /// Resolve `attack` of the creature at `attacker` against the creature at `target` with the
+    /// d20 showing `d20`, dealing the damage on a hit.
+    fn resolve_attack(
+        &mut self,
+        attacker: usize,
+        target: usize,
+        attack: &Attack,
+        d20: u8,
+    ) -> Result<(), String> {
+        let encounter = &self.current_encounter;
+        let (Some(creature), Some(defender)) = (
+            encounter.creatures.get(attacker),
+            encounter.creatures.get(target),
+        ) else {
+            return Ok(());
+        };
+        let penalty = encounter
+            .settings
+            .exhaustion
+            .d20_penalty(creature.exhaustion());
+        let roll = attack.check(d20, penalty, defender.ac());
+        let summary = format!(
+            "{} {} {} with {} ({} vs AC {})",
+            creature.name(),
+            match (roll.hit, roll.critical) {
+                (true, true) => "crits",
+                (true, false) => "hits",
+                (false, _) => "misses",
+            },
+            defender.name(),
+            attack.name,
+            if roll.critical || d20 <= 1 {
+                format!("natural {}", d20)
+            } else {
+                roll.total.to_string()
+            },
+            defender.ac()
+        );
+
+        if !roll.hit {
+            self.status_message = Some(summary);
+            return Ok(());
+        }
+        let damage = attack.roll_damage(roll.critical)?;
+        self.damage_creature(target, damage);
+        self.status_message = Some(match self.status_message.take() {
+            Some(message) => format!("{}: {}", summary, message),
+            None => summary,
+        });
+        Ok(())
+    }
+
+    /// Prompt for a lair action, regional effect or reminder to add to the turn order.
+    pub fn add_initiative_entry(&mut self) {
+        self.open_prompt(Prompt::new(PromptKind::AddEntry));
+    }
+
+    /// Remove the initiative entry whose turn it is, giving the turn back to the creature before
+    /// it.
+    pub fn remove_active_entry(&mut self) {
+        let encounter = &mut self.current_encounter;
+        self.status_message = Some(match encounter.active_entry {
+            Some(index) => match encounter.remove_entry(index) {
+                Some(entry) => format!("Removed {}", entry.label()),
+                None => return,
+            },
+            None => String::from("Pass the turn to an event to remove it"),
+        });
+    }
+
+    /// Prompt for the file to save the encounter to.
+    pub fn save_encounter(&mut self) {
+        self.open_prompt(Prompt::new(PromptKind::SaveEncounter));
+    }
+
+    /// Prompt for the file to open an encounter from, replacing the current one.
+    pub fn open_encounter(&mut self) {
+        self.open_prompt(Prompt::new(PromptKind::OpenEncounter));
+    }
+
+    /// Ask for confirmation to remove the creature under the cursor.
+    pub fn remove_selected_creature(&mut self) {
+        if let Some(id) = self.selected_id() {
+            self.open_prompt(Prompt::new(PromptKind::RemoveCreature(id)));
+        }
+    }
+
+    /// Add a fresh copy of the creature under the cursor right below it.
+    pub fn duplicate_selected_creature(&mut self) {
+        self.current_encounter
+            .duplicate_creature(self.current_encounter.cursor_index);
+        self.sync_table_state();
+    }
+
+    /// Move the creature under the cursor one row up in the turn order.
+    pub fn move_selected_creature_up(&mut self) {
+        self.current_encounter
+            .move_creature_up(self.current_encounter.cursor_index);
+        self.sync_table_state();
+    }
+
+    /// Move the creature under the cursor one row down in the turn order.
+    pub fn move_selected_creature_down(&mut self) {
+        self.current_encounter
+            .move_creature_down(self.current_encounter.cursor_index);
+        self.sync_table_state();
+    }
+
+    /// Queue `prompt` and show the prompt panel.
+    pub fn open_prompt(&mut self, prompt: Prompt) {
+        self.prompts.push_back(prompt);
+        self.current_panel = Panel::Prompt;
+    }
+
+    /// Apply the value entered in the current prompt. An invalid value keeps the prompt open
+    /// with an error.
+    pub fn submit_prompt(&mut self) {
+        let Some(prompt) = self.prompts.front() else {
+            self.current_panel = Panel::InitiativeTable;
+            return;
+        };
+        let value = prompt.input.value().trim().to_string();
+
+        let result = match prompt.kind {
+            PromptKind::Initiative(id) => {
+                self.submit_for(id, |app, i| app.submit_initiative(i, &value))
+            }
+            PromptKind::Damage(id) => self.submit_for(id, |app, i| {
+                roll_damage(&value).map(|damage| app.damage_creature(i, damage))
+            }),
+            PromptKind::Heal(id) => self.submit_for(id, |app, i| {
+                roll_amount(&value).map(|amount| app.heal_creature(i, amount))
+            }),
+            PromptKind::TempHp(id) => self.submit_for(id, |app, i| {
+                roll_amount(&value).map(|amount| app.grant_temp_hp(i, amount))
+            }),
+            PromptKind::DeathSave(id) => {
+                self.submit_for(id, |app, i| app.submit_death_save(i, &value))
+            }
+            PromptKind::RepeatSave(id, status) => {
+                self.submit_for(id, |app, i| app.submit_repeat_save(i, status, &value))
+            }
+            PromptKind::ConditionDuration(id, status) => self.submit_for(id, |app, i| {
+                app.submit_condition_duration(i, status, &value)
+            }),
+            PromptKind::ConditionSave(id, status) => {
+                self.submit_for(id, |app, i| app.submit_condition_save(i, status, &value))
+            }
+            PromptKind::Concentration(id) => {
+                self.submit_for(id, |app, i| app.submit_concentration(i, &value))
+            }
+            PromptKind::ConcentrationSave(id, dc) => {
+                self.submit_for(id, |app, i| app.submit_concentration_save(i, dc, &value))
+            }
+            PromptKind::LegendaryActions(id, left) => {
+                self.submit_for(id, |app, i| app.submit_legendary_actions(i, left, &value))
+            }
+            PromptKind::LegendaryResistances(id) => {
+                self.submit_for(id, |app, i| app.submit_legendary_resistances(i, &value))
+            }
+            PromptKind::MassSave => parse_save(&value).map(|(dc, ability)| {
+                self.mass_save.dc = dc;
+                self.mass_save.ability = ability;
+                self.mass_save.rolls.clear();
+            }),
+            PromptKind::MassSaveDamage => {
+                roll_damage(&value).map(|damage| self.damage_mass_save(damage))
+            }
+            PromptKind::RechargeAbility(id) => {
+                self.submit_for(id, |app, i| app.submit_recharge_ability(i, &value))
+            }
+            PromptKind::Attack(attacker, target) => match self.current_encounter.index_of(target) {
+                Some(target) => {
+                    self.submit_for(attacker, |app, i| app.submit_attack(i, target, &value))
+                }
+                None => Ok(()),
+            },
+            PromptKind::AddEntry => value.parse::<InitiativeEntry>().map(|entry| {
+                self.status_message = Some(format!("Added {}", entry.label()));
+                self.current_encounter.add_entry(entry);
+            }),
+            PromptKind::SaveEncounter => {
+                let path = (!value.is_empty()).then_some(&value);
+                storage::store_encounter(&self.current_encounter, path)
+                    .map(|path| {
+                        self.status_message =
+                            Some(format!("Saved the encounter to {}", path.display()));
+                    })
+                    .map_err(|e| format!("Cannot save the encounter: {}", e))
+            }
+            PromptKind::OpenEncounter => storage::load_encounter(&value)
+                .map(|encounter| {
+                    self.current_encounter = encounter;
+                    self.sync_table_state();
+                    self.status_message = Some(format!("Opened {}", value));
+                })
+                .map_err(|e| format!("Cannot open the encounter: {}", e)),
+            PromptKind::RemoveCreature(id) => self.submit_for(id, |app, i| {
+                if matches!(&value.to_lowercase()[..], "y" | "yes")
+                    && let Some((_, events)) = app.current_encounter.remove_creature(i)
+                {
+                    app.sync_table_state();
+                    app.report_turn_events(events);
+                }
+                Ok(())
+            }),
+        };
+
+        match result {
+            Ok(()) => self.close_prompt(),
+            Err(error) => {
+                if let Some(prompt) = self.prompts.front_mut() {
+                    prompt.error = Some(error);
+                }
+            }
+        }
+    }
+
+    /// Call `submit` with the index the creature with `id` is at now. Nothing is done if it left
+    /// the encounter since the prompt was queued.
+    fn submit_for(
+        &mut self,
+        id: CreatureId,
+        submit: impl FnOnce(&mut App, usize) -> Result<(), String>,
+    ) -> Result<(), String> {
+        match self.current_encounter.index_of(id) {
+            Some(index) => submit(self, index),
+            None => Ok(()),
+        }
+    }
+
+    /// Close the current prompt, showing the next one if there is any.
+    pub fn close_prompt(&mut self) {
+        let Some(closed) = self.prompts.pop_front() else {
+            self.current_panel = Panel::InitiativeTable;
+            return;
+        };
+
+        let initiative_pending = self
+            .prompts
+            .iter()
+            .any(|p| matches!(p.kind, PromptKind::Initiative(_)));
+        if matches!(closed.kind, PromptKind::Initiative(_)) && !initiative_pending {
+            if self.starting_combat {
+                self.start_combat();
+            } else {
+                self.sort_initiative();
+            }
+        }
+
+        if self.prompts.is_empty() {
+            self.current_panel = match closed.kind {
+                PromptKind::ConditionDuration(..) | PromptKind::ConditionSave(..) => {
+                    Panel::Conditions
+                }
+                PromptKind::MassSave | PromptKind::MassSaveDamage => Panel::MassSave,
+                _ => Panel::InitiativeTable,
+            };
+        }
+    }
+
+    fn submit_initiative(&mut self, index: usize, value: &str) -> Result<(), String> {
+        let Some(creature) = self.current_encounter.creatures.get_mut(index) else {
+            return Ok(());
+        };
+        if value.is_empty() {
+            creature.roll_initiative();
+        } else {
+            let init = value
+                .parse::<u8>()
+                .map_err(|_| String::from("Expected a number"))?;
+            creature.set_initiative(init);
+        }
+        Ok(())
+    }
+
+    /// Record the death save of the creature at `index`, rolling the d20 if `value` is empty. Like
+    /// every d20 test it takes the exhaustion penalty of the 2024 rules.
+    fn submit_death_save(&mut self, index: usize, value: &str) -> Result<(), String> {
+        let roll = if value.is_empty() {
+            roll_d20()
+        } else {
+            match value.parse::<u8>() {
+                Ok(roll @ 1..=20) => roll,
+                _ => return Err(String::from("Expected a d20 roll from 1 to 20")),
+            }
+        };
+        let rules = self.current_encounter.settings.exhaustion;
+        let Some(creature) = self.current_encounter.creatures.get_mut(index) else {
+            return Ok(());
+        };
+        let penalty = rules.d20_penalty(creature.exhaustion());
+        let Some(outcome) = creature.record_death_save(roll, penalty) else {
+            return Ok(());
+        };
+
+        let saves = creature.death_saves().copied().unwrap_or_default();
+        let result = match outcome {
+            DeathSaveOutcome::Success => format!("succeeds ({}/3)", saves.successes),
+            DeathSaveOutcome::Failure => format!("fails ({}/3)", saves.failures),
+            DeathSaveOutcome::Stabilized => String::from("succeeds and is stable"),
+            DeathSaveOutcome::Revived => String::from("regains 1 HP"),
+            DeathSaveOutcome::Died => String::from("fails and dies"),
+        };
+        let roll = match penalty {
+            0 => roll.to_string(),
+            penalty => format!("{} ({:+} exhaustion)", roll, penalty),
+        };
+        self.status_message = Some(format!(
+            "{} rolls {} on a death save and {}",
+            creature.name(),
+            roll,
+            result
+        ));
+        self.end_lost_effects();
+        Ok(())
+    }
+
+    /// Record the repeated save of the creature at `index` against `status`, rolling it if
+    /// `value` is empty. A rolled save includes the save proficiency and the exhaustion penalty of
+    /// the 2024 rules. The status ends on a success.
+    fn submit_repeat_save(
+        &mut self,
+        index: usize,
+        status: Status,
+        value: &str,
+    ) -> Result<(), String> {
+        let rules = self.current_encounter.settings.exhaustion;
+        let Some(creature) = self.current_encounter.creatures.get_mut(index) else {
+            return Ok(());
+        };
+        let Some(save) = creature
+            .status_effects()
+            .iter()
+            .find(|e| e.status == status)
+            .and_then(|e| e.save)
+        else {
+            return Ok(());
+        };
+
+        let total = if value.is_empty() {
+            i32::from(roll_d20())
+                + i32::from(creature.save_modifier(save.ability))
+                + rules.d20_penalty(creature.exhaustion())
+        } else {
+            value
+                .parse::<i32>()
+                .map_err(|_| String::from("Expected the save total"))?
+        };
+        let result = if total >= i32::from(save.dc) {
+            creature.remove_status(status);
+            format!("is no longer {}", status)
+        } else {
+            format!("stays {}", status)
+        };
+        self.status_message = Some(format!(
+            "{} rolls {} on a {} save and {}",
+            creature.name(),
+            total,
+            save,
+            result
+        ));
+        Ok(())
+    }
+
+    pub fn select_panel(&mut self, panel: Panel) {
+        self.current_panel = panel;
+    }
+
+    /// Open the editor pre-filled with the creature under the cursor.
+    pub fn edit_selected_creature(&mut self) {
+        let index = self.current_encounter.cursor_index;
+        if let Some(creature) = self.current_encounter.creatures.get(index) {
+            self.editor_state = EditorState::from_creature(creature);
+            self.current_panel = Panel::Editor;
+        }
+    }
+
+    /// Add the creatures described by the editor to the encounter, or write them back to the
+    /// creature being edited, and close the editor. Several copies are numbered after the ones
+    /// already in the encounter. If any input is invalid the editor stays open with the
+    /// errors set instead.
+    pub fn submit_editor(&mut self) {
+        match self.editor_state.parse() {
+            Ok(mut creatures) => {
+                match self
+                    .editor_state
+                    .editing
+                    .and_then(|id| self.current_encounter.creature_mut(id))
+                {
+                    Some(existing) => existing.update_details(creatures.remove(0)),
+                    None => {
+                        if creatures.len() > 1 {
+                            let first =
+                                self.current_encounter.next_copy_number(creatures[0].name());
+                            for (i, creature) in creatures.iter_mut().enumerate() {
+                                let name = format!("{} {}", creature.name(), first + i);
+                                creature.set_name(name);
+                            }
+                        }
+                        for creature in creatures {
+                            self.add_creature(creature);
+                        }
+                    }
+                }
+                self.editor_state.reset();
+                self.current_panel = Panel::InitiativeTable;
+            }
+            Err(errors) => self.editor_state.errors = errors,
+        }
+    }
+}
+
+impl Serialize for App {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerializableApp::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for App {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        SerializableApp::deserialize(deserializer).map(Into::into)
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        let mut app = App {
+            main_table_state: TableState::default(),
+            current_encounter: Encounter::default(),
+            current_panel: Panel::InitiativeTable,
+            editor_state: EditorState::default(),
+            setting_index: 0,
+            prompts: VecDeque::new(),
+            condition_picker: ConditionPicker::default(),
+            mass_save: MassSave::default(),
+            starting_combat: false,
+            status_message: None,
+        };
+        app.sync_table_state();
+        app
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tui_input::Input;
+
+    use super::{
+        App, CreatureKind, EditorField, EditorState, Panel, PromptKind, format_hp, parse_cr,
+        roll_amount, roll_damage,
+    };
+    use crate::model::{
+        Ability, Creature, Damage, DamageType, Duration, InitiativeRoll, Status, StatusEffect,
+    };
+
+    #[test]
+    fn app_serde_round_trips_encounter_state() {
+        let mut app = App::default();
+        app.select_panel(Panel::Editor);
+        app.current_encounter
+            .add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        app.current_encounter
+            .add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+        app.current_encounter.initiative_index = 1;
+        app.sync_table_state();
+
+        let json = serde_json::to_string(&app).unwrap();
+        let mut restored: App = serde_json::from_str(&json).unwrap();
+        restored.sync_table_state();
+
+        assert_eq!(restored.current_encounter.initiative_index, 1);
+        assert_eq!(restored.main_table_state.selected(), Some(1));
+        assert_eq!(restored.current_encounter.creatures.len(), 2);
+        assert_eq!(restored.current_panel, Panel::Editor);
+    }
+
+    #[test]
+    fn submit_editor_adds_creature_and_closes_editor() {
+        let mut app = App::default();
+        app.select_panel(Panel::Editor);
+        app.editor_state.name_input = Input::new(String::from("Goblin"));
+        app.editor_state.cur_hp_input = Input::new(String::from("5"));
+        app.editor_state.max_hp_input = Input::new(String::from("7"));
+        app.editor_state.ac_input = Input::new(String::from("15"));
+        app.editor_state.cr_input = Input::new(String::from("1/4"));
+
+        app.submit_editor();
+
+        assert_eq!(app.current_panel, Panel::InitiativeTable);
+        assert_eq!(app.current_encounter.creatures.len(), 1);
+        let goblin = &app.current_encounter.creatures[0];
+        assert_eq!(goblin.name(), "Goblin");
+        assert_eq!(goblin.hp(), 5);
+        assert_eq!(goblin.max_hp(), 7);
+        assert_eq!(goblin.ac(), 15);
+        assert_eq!(goblin.get_level_or_cr(), 0.25);
+        assert!(app.editor_state.name_input.value().is_empty());
+    }
+
+    #[test]
+    fn submit_editor_reports_invalid_fields() {
+        let mut app = App::default();
+        app.select_panel(Panel::Editor);
+        app.editor_state.max_hp_input = Input::new(String::from("lots"));
+        app.editor_state.ac_input = Input::new(String::from("-1"));
+
+        app.submit_editor();
+
+        assert_eq!(app.current_panel, Panel::Editor);
+        assert!(app.current_encounter.creatures.is_empty());
+        assert!(app.editor_state.error(EditorField::Name).is_some());
+        assert!(app.editor_state.error(EditorField::MaxHP).is_some());
+        assert!(app.editor_state.error(EditorField::AC).is_some());
+        assert!(app.editor_state.error(EditorField::CurrentHP).is_none());
+    }
+
+    #[test]
+    fn parse_cr_accepts_fractions_and_decimals() {
+        assert_eq!(parse_cr("1/8"), Some(0.125));
+        assert_eq!(parse_cr("0.5"), Some(0.5));
+        assert_eq!(parse_cr("12"), Some(12.0));
+        assert_eq!(parse_cr("1/0"), None);
+        assert_eq!(parse_cr("-1"), None);
+    }
+
+    #[test]
+    fn submit_editor_creates_player_with_stats() {
+        let mut app = App::default();
+        app.editor_state.previous_kind();
+        app.editor_state.name_input = Input::new(String::from("Alice"));
+        app.editor_state.max_hp_input = Input::new(String::from("24"));
+        app.editor_state.cr_input = Input::new(String::from("3"));
+        app.editor_state.stat_inputs[Ability::Dexterity as usize] = Input::new(String::from("16"));
+
+        app.submit_editor();
+
+        let alice = &app.current_encounter.creatures[0];
+        assert!(matches!(alice, Creature::Player { level: 3, .. }));
+        assert_eq!(alice.stats().dex_mod(), 3);
+        assert_eq!(alice.stats().strength, 10);
+    }
+
+    #[test]
+    fn submit_editor_creates_important_npcs() {
+        let mut app = App::default();
+        app.editor_state.next_kind();
+        assert_eq!(app.editor_state.kind, CreatureKind::ImportantNpc);
+        app.editor_state.name_input = Input::new(String::from("Strahd"));
+        app.editor_state.max_hp_input = Input::new(String::from("144"));
+
+        app.submit_editor();
+
+        let strahd = &app.current_encounter.creatures[0];
+        assert!(matches!(
+            strahd,
+            Creature::Monster {
+                important: true,
+                ..
+            }
+        ));
+        assert_eq!(
+            EditorState::from_creature(strahd).kind,
+            CreatureKind::ImportantNpc
+        );
+    }
+
+    #[test]
+    fn submit_editor_rejects_out_of_range_scores_and_levels() {
+        let mut app = App::default();
+        app.editor_state.previous_kind();
+        app.editor_state.name_input = Input::new(String::from("Alice"));
+        app.editor_state.max_hp_input = Input::new(String::from("24"));
+        app.editor_state.cr_input = Input::new(String::from("1/2"));
+        app.editor_state.stat_inputs[Ability::Wisdom as usize] = Input::new(String::from("31"));
+
+        app.submit_editor();
+
+        assert!(app.current_encounter.creatures.is_empty());
+        assert!(app.editor_state.error(EditorField::CR).is_some());
+        assert!(
+            app.editor_state
+                .error(EditorField::Stat(Ability::Wisdom))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn editing_a_creature_keeps_its_combat_state() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_monster(
+            "Gobin",
+            7,
+            15,
+            None,
+            None,
+            Some(0.25),
+        ));
+        let goblin = &mut app.current_encounter.creatures[0];
+        goblin.set_initiative(12);
+
+        app.edit_selected_creature();
+        assert_eq!(app.current_panel, Panel::Editor);
+        assert_eq!(app.editor_state.cr_input.value(), "1/4");
+        app.editor_state.name_input = Input::new(String::from("Goblin"));
+        app.editor_state.max_hp_input = Input::new(String::from("9"));
+        app.submit_editor();
+
+        assert_eq!(app.current_encounter.creatures.len(), 1);
+        let goblin = &app.current_encounter.creatures[0];
+        assert_eq!(goblin.name(), "Goblin");
+        assert_eq!(goblin.max_hp(), 9);
+        assert_eq!(goblin.hp(), 7);
+        assert_eq!(goblin.get_initiative(), Some(12));
+        assert_eq!(app.editor_state.editing, None);
+    }
+
+    #[test]
+    fn editing_hp_above_0_wakes_a_downed_player_up() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 14, None, None, None));
+        app.damage_creature(0, Damage::new(20));
+        app.current_encounter.creatures[0].record_death_save(5, 0);
+
+        app.edit_selected_creature();
+        app.editor_state.cur_hp_input = Input::new(String::from("6"));
+        app.submit_editor();
+
+        let alice = &app.current_encounter.creatures[0];
+        assert_eq!(alice.hp(), 6);
+        assert!(!alice.get_statuses().contains(&Status::Unconscious));
+        assert_eq!(alice.death_saves().unwrap().failures, 0);
+        assert!(!alice.needs_death_save());
+    }
+
+    #[test]
+    fn editing_hp_down_to_0_knocks_a_player_out() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 14, None, None, None));
+
+        app.edit_selected_creature();
+        app.editor_state.cur_hp_input = Input::new(String::from("0"));
+        app.submit_editor();
+
+        let alice = &app.current_encounter.creatures[0];
+        assert!(alice.is_downed());
+        assert!(alice.get_statuses().contains(&Status::Unconscious));
+        assert!(alice.needs_death_save());
+    }
+
+    #[test]
+    fn submit_editor_adds_numbered_copies_with_rolled_hp() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_monster("Goblin 1", 7, 15, None, None, None));
+        app.editor_state.name_input = Input::new(String::from("Goblin"));
+        app.editor_state.max_hp_input = Input::new(String::from("2d6"));
+        app.editor_state.amount_input = Input::new(String::from("3"));
+
+        app.submit_editor();
+
+        let names: Vec<_> = app
+            .current_encounter
+            .creatures
+            .iter()
+            .map(|c| c.name())
+            .collect();
+        assert_eq!(names, ["Goblin 1", "Goblin 2", "Goblin 3", "Goblin 4"]);
+        assert!(
+            app.current_encounter.creatures[1..]
+                .iter()
+                .all(|c| (2..=12).contains(&c.max_hp()) && c.hp() == c.max_hp())
+        );
+    }
+
+    #[test]
+    fn submit_editor_rejects_current_hp_with_hit_dice() {
+        let mut app = App::default();
+        app.editor_state.name_input = Input::new(String::from("Goblin"));
+        app.editor_state.max_hp_input = Input::new(String::from("2d6"));
+        app.editor_state.cur_hp_input = Input::new(String::from("3"));
+        app.editor_state.amount_input = Input::new(String::from("0"));
+
+        app.submit_editor();
+
+        assert!(app.current_encounter.creatures.is_empty());
+        assert!(app.editor_state.error(EditorField::CurrentHP).is_some());
+        assert!(app.editor_state.error(EditorField::Amount).is_some());
+    }
+
+    #[test]
+    fn rolling_for_monsters_prompts_every_player() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        app.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        app.add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+        app.current_encounter.creatures[2].set_initiative(0);
+
+        app.roll_initiative(InitiativeRoll::MonstersOnly);
+        assert_eq!(app.current_panel, Panel::Prompt);
+        assert_eq!(app.prompts.len(), 2);
+        assert_eq!(app.prompts[1].input.value(), "0");
+
+        app.prompts[0].input = Input::new(String::from("high"));
+        app.submit_prompt();
+        assert!(app.prompts[0].error.is_some());
+
+        app.prompts[0].input = Input::new(String::from("25"));
+        app.submit_prompt();
+        app.submit_prompt();
+
+        assert_eq!(app.current_panel, Panel::InitiativeTable);
+        let encounter = &app.current_encounter;
+        assert_eq!(encounter.creatures[0].name(), "Alice");
+        assert_eq!(encounter.creatures[2].name(), "Bob");
+        assert!(encounter.creatures.iter().all(|c| c.has_initiative()));
+    }
+
+    #[test]
+    fn rolling_initiative_restarts_the_rounds() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        app.add_creature(Creature::new_monster("Wolf", 11, 13, None, None, None));
+        app.increment_initiative_order();
+        app.increment_initiative_order();
+        assert_eq!(app.current_encounter.round, 2);
+
+        app.roll_initiative(InitiativeRoll::Everyone);
+
+        assert_eq!(app.current_encounter.round, 1);
+        assert_eq!(app.current_encounter.initiative_index, 0);
+    }
+
+    #[test]
+    fn removing_a_creature_needs_confirmation() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+
+        app.remove_selected_creature();
+        app.prompts[0].input = Input::new(String::from("n"));
+        app.submit_prompt();
+        assert_eq!(app.current_encounter.creatures.len(), 1);
+
+        app.remove_selected_creature();
+        app.prompts[0].input = Input::new(String::from("y"));
+        app.submit_prompt();
+        assert!(app.current_encounter.creatures.is_empty());
+        assert_eq!(app.current_panel, Panel::InitiativeTable);
+        assert_eq!(app.main_table_state.selected(), None);
+    }
+
+    #[test]
+    fn damage_and_healing_prompts_report_the_outcome() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 10, None, None, None));
+
+        app.damage_selected_creature();
+        app.prompts[0].input = Input::new(String::from("1d4+10"));
+        app.submit_prompt();
+        let hp = app.current_encounter.creatures[0].hp();
+        assert!((6..=9).contains(&hp));
+        assert!(app.status_message.as_ref().unwrap().contains("survives"));
+
+        app.damage_selected_creature();
+        app.prompts[0].input = Input::new(String::from("25"));
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Alice takes 25 damage and is downed")
+        );
+
+        app.damage_selected_creature();
+        app.prompts[0].input = Input::new(String::from("3 sharp"));
+        app.submit_prompt();
+        assert!(app.prompts[0].error.is_some());
+        app.close_prompt();
+
+        app.heal_selected_creature();
+        app.prompts[0].input = Input::new(String::from("5"));
+        app.submit_prompt();
+        assert_eq!(app.current_encounter.creatures[0].hp(), 5);
+        assert_eq!(app.current_panel, Panel::InitiativeTable);
+
+        app.heal_selected_creature();
+        app.prompts[0].input = Input::new(String::from("30"));
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Alice heals 15 and is at 20/20 HP")
+        );
+    }
+
+    #[test]
+    fn queued_prompts_follow_their_creature_when_the_order_changes() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 10, None, None, None));
+        app.add_creature(Creature::new_monster("Ogre", 59, 11, None, None, None));
+        app.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+
+        app.current_encounter.cursor_index = 1;
+        app.damage_selected_creature();
+        app.current_encounter.cursor_index = 2;
+        app.damage_selected_creature();
+        app.move_selected_creature_up();
+        app.current_encounter.cursor_index = 0;
+        app.move_selected_creature_down();
+        let names: Vec<&str> = app
+            .current_encounter
+            .creatures
+            .iter()
+            .map(|c| c.name())
+            .collect();
+        assert_eq!(names, ["Goblin", "Alice", "Ogre"]);
+
+        app.prompts[0].input = Input::new(String::from("9"));
+        app.submit_prompt();
+        assert_eq!(app.current_encounter.creatures[2].hp(), 50);
+        assert_eq!(app.current_encounter.creatures[1].hp(), 20);
+
+        // The goblin leaves before its prompt is answered
+        app.current_encounter.remove_creature(0);
+        app.prompts[0].input = Input::new(String::from("9"));
+        app.submit_prompt();
+        assert!(app.prompts.is_empty());
+        assert_eq!(app.current_encounter.creatures[0].hp(), 20);
+    }
+
+    #[test]
+    fn downed_players_are_asked_for_death_saves_on_their_turn() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 10, None, None, None));
+        app.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        app.damage_creature(0, Damage::new(20));
+
+        app.increment_initiative_order();
+        assert!(app.prompts.is_empty());
+        app.increment_initiative_order();
+        assert_eq!(app.current_panel, Panel::Prompt);
+
+        app.prompts[0].input = Input::new(String::from("21"));
+        app.submit_prompt();
+        assert!(app.prompts[0].error.is_some());
+        app.prompts[0].input = Input::new(String::from("1"));
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Alice rolls 1 on a death save and fails (2/3)")
+        );
+        assert!(app.prompts.is_empty());
+
+        app.damage_creature(0, Damage::new(3));
+        assert!(app.current_encounter.creatures[0].is_dead());
+    }
+
+    #[test]
+    fn removing_the_active_creature_asks_for_the_next_death_save() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        app.add_creature(Creature::new_player("Alice", 20, 10, None, None, None));
+        app.damage_creature(1, Damage::new(20));
+
+        app.remove_selected_creature();
+        app.prompts[0].input = Input::new(String::from("y"));
+        app.submit_prompt();
+
+        let alice = app.current_encounter.creatures[0].id();
+        assert_eq!(app.current_encounter.initiative_index, 0);
+        assert_eq!(app.prompts.len(), 1);
+        assert_eq!(app.prompts[0].kind, PromptKind::DeathSave(alice));
+        assert_eq!(app.current_panel, Panel::Prompt);
+    }
+
+    #[test]
+    fn turn_passing_announces_expiries_and_asks_for_saves() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 10, None, None, None));
+        app.add_creature(Creature::new_monster("Ghoul", 22, 12, None, None, None));
+        app.current_encounter.add_status_effect(
+            1,
+            StatusEffect::new(Status::Prone).with_duration(Duration::Rounds(1)),
+        );
+        app.current_encounter.add_status_effect(
+            1,
+            StatusEffect::new(Status::Paralyzed).with_save(13, Ability::Wisdom),
+        );
+
+        app.increment_initiative_order();
+        app.increment_initiative_order();
+        assert_eq!(app.status_message.as_deref(), Some("Prone ends on Ghoul"));
+        assert_eq!(app.current_panel, Panel::Prompt);
+
+        app.prompts[0].input = Input::new(String::from("9"));
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Ghoul rolls 9 on a DC 13 WIS save and stays Paralyzed")
+        );
+        assert_eq!(
+            app.current_encounter.creatures[1].get_statuses(),
+            [Status::Paralyzed]
+        );
+    }
+
+    #[test]
+    fn damage_asks_concentrating_creatures_for_a_con_save() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 40, 10, None, None, None));
+        app.add_creature(Creature::new_monster("Ghoul", 22, 12, None, None, None));
+        app.current_encounter.creatures[1].add_status(Status::Paralyzed);
+
+        app.current_encounter.cursor_index = 0;
+        app.concentrate_selected_creature();
+        app.prompts[0].input = Input::new(String::from("Hold Person 10"));
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Alice concentrates on Hold Person")
+        );
+        app.current_encounter.cursor_index = 1;
+        app.open_condition_picker();
+        select_condition(&mut app, Status::Paralyzed);
+        app.link_selected_condition();
+
+        let alice = app.current_encounter.creatures[0].id();
+        app.damage_creature(0, Damage::new(12));
+        assert_eq!(
+            app.prompts[0].kind,
+            PromptKind::ConcentrationSave(alice, 10)
+        );
+        app.prompts[0].input = Input::new(String::from("10"));
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Alice rolls 10 on a DC 10 CON save and keeps concentrating on Hold Person")
+        );
+
+        app.damage_creature(0, Damage::new(24));
+        assert_eq!(
+            app.prompts[0].kind,
+            PromptKind::ConcentrationSave(alice, 12)
+        );
+        app.prompts[0].input = Input::new(String::from("11"));
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Alice rolls 11 on a DC 12 CON save and loses concentration on Hold Person")
+        );
+        assert!(app.current_encounter.creatures[1].get_statuses().is_empty());
+        assert_eq!(app.current_panel, Panel::InitiativeTable);
+    }
+
+    /// Highlight the condition of `status` in the condition picker.
+    fn select_condition(app: &mut App, status: Status) {
+        app.condition_picker.selected = Status::ALL
+            .iter()
+            .position(|s| s.same_condition(&status))
+            .unwrap();
+    }
+
+    #[test]
+    fn condition_picker_toggles_and_adjusts_conditions() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 10, None, None, None));
+        app.add_creature(Creature::new_monster("Ogre", 59, 11, None, None, None));
+        let ogre = app.current_encounter.creatures[1].id();
+        app.open_condition_picker();
+        assert_eq!(app.current_panel, Panel::Conditions);
+
+        app.toggle_selected_condition();
+        select_condition(&mut app, Status::Exhaustion(1));
+        app.adjust_selected_condition(1);
+        app.adjust_selected_condition(1);
+        select_condition(&mut app, Status::Grappled(ogre));
+        app.toggle_selected_condition();
+        assert_eq!(
+            app.current_encounter.creatures[0].get_statuses(),
+            [
+                Status::Blinded,
+                Status::Exhaustion(2),
+                Status::Grappled(ogre)
+            ]
+        );
+
+        select_condition(&mut app, Status::Blinded);
+        app.toggle_selected_condition();
+        app.edit_selected_condition_duration();
+        assert!(app.prompts.is_empty());
+        select_condition(&mut app, Status::Exhaustion(1));
+        app.edit_selected_condition_duration();
+        app.prompts[0].input = Input::new(String::from("3"));
+        app.submit_prompt();
+        assert_eq!(app.current_panel, Panel::Conditions);
+        let effect = app.current_encounter.creatures[0].status_effects()[0];
+        assert_eq!(effect.status, Status::Exhaustion(2));
+        assert_eq!(effect.duration, Duration::Rounds(3));
+
+        app.edit_selected_condition_save();
+        app.prompts[0].input = Input::new(String::from("13 con"));
+        app.submit_prompt();
+        let save = app.current_encounter.creatures[0].status_effects()[0].save;
+        assert_eq!(
+            save.map(|s| (s.dc, s.ability)),
+            Some((13, Ability::Constitution))
+        );
+    }
+
+    #[test]
+    fn roll_amount_accepts_numbers_and_dice() {
+        assert_eq!(roll_amount("12"), Ok(12));
+        assert!((3..=8).contains(&roll_amount("1d6+2").unwrap()));
+        assert!(roll_amount("lots").is_err());
+    }
+
+    #[test]
+    fn temp_hp_prompt_grants_and_removes_temp_hp() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 10, None, None, None));
+
+        app.grant_selected_temp_hp();
+        app.prompts[0].input = Input::new(String::from("5"));
+        app.submit_prompt();
+        assert_eq!(app.current_encounter.creatures[0].temp_hp(), 5);
+        assert_eq!(format_hp(&app.current_encounter.creatures[0]), "20+5");
+
+        app.grant_selected_temp_hp();
+        app.prompts[0].input = Input::new(String::from("0"));
+        app.submit_prompt();
+        assert_eq!(app.current_encounter.creatures[0].temp_hp(), 0);
+        assert_eq!(format_hp(&app.current_encounter.creatures[0]), "20");
+    }
+
+    #[test]
+    fn roll_damage_reads_an_optional_type() {
+        assert_eq!(roll_damage("12"), Ok(Damage::new(12)));
+        assert_eq!(
+            roll_damage("12 fire"),
+            Ok(Damage::typed(12, DamageType::Fire))
+        );
+        assert_eq!(
+            roll_damage("4 + 8 magical piercing").map(|d| d.damage_type),
+            Ok(Some(DamageType::MagicalPiercing))
+        );
+        assert!(roll_damage("12 sharp").is_err());
+        assert_eq!(
+            roll_damage("7 fire crit"),
+            Ok(Damage::typed(7, DamageType::Fire).critical())
+        );
+        assert!(roll_damage("crit").is_err());
+    }
+
+    #[test]
+    fn huge_damage_kills_instead_of_wrapping_around() {
+        let mut app = App::default();
+        let mut troll = Creature::new_monster("Troll", 84, 15, None, None, None);
+        troll.set_damage_defenses(Vec::new(), vec![DamageType::Fire], Vec::new());
+        app.add_creature(troll);
+
+        app.damage_selected_creature();
+        app.prompts[0].input = Input::new(String::from("3000000000 fire"));
+        app.submit_prompt();
+
+        let troll = &app.current_encounter.creatures[0];
+        assert_eq!(troll.hp(), 0);
+        assert!(troll.is_dead());
+    }
+
+    #[test]
+    fn editor_round_trips_damage_defenses() {
+        let mut app = App::default();
+        app.editor_state.name_input = Input::new(String::from("Ghoul"));
+        app.editor_state.max_hp_input = Input::new(String::from("22"));
+        app.editor_state.resistances_input = Input::new(String::from("necrotic"));
+        app.editor_state.immunities_input = Input::new(String::from("Poison, psychic"));
+        app.submit_editor();
+
+        app.damage_creature(0, Damage::typed(10, DamageType::Necrotic));
+        assert_eq!(app.current_encounter.creatures[0].hp(), 17);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Ghoul takes 5 necrotic damage (resisted 10) and survives with 17 HP")
+        );
+
+        app.edit_selected_creature();
+        assert_eq!(app.editor_state.immunities_input.value(), "poison, psychic");
+        app.editor_state.vulnerabilities_input = Input::new(String::from("ice"));
+        app.submit_editor();
+        assert!(
+            app.editor_state
+                .error(EditorField::Vulnerabilities)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn legendary_prompts_spend_actions_and_resistances() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 10, None, None, None));
+        app.add_creature(Creature::new_player("Bob", 20, 10, None, None, None));
+        app.add_creature(Creature::new_monster("Dragon", 200, 19, None, None, None));
+        app.current_encounter.creatures[2].set_legendary(3, 3);
+
+        let dragon = app.current_encounter.creatures[2].id();
+        app.increment_initiative_order();
+        assert_eq!(app.prompts[0].kind, PromptKind::LegendaryActions(dragon, 3));
+        app.prompts[0].input = Input::new(String::from("4"));
+        app.submit_prompt();
+        assert_eq!(app.prompts[0].error.as_deref(), Some("Only 3 left"));
+        app.prompts[0].input = Input::new(String::from("2"));
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Dragon spends 2 legendary actions (1/3 left)")
+        );
+
+        app.current_encounter.cursor_index = 2;
+        app.use_selected_legendary_resistance();
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Dragon uses a legendary resistance (2/3 left)")
+        );
+
+        app.current_encounter.cursor_index = 0;
+        app.spend_selected_legendary_actions();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Alice has no legendary actions")
+        );
+
+        // Asked at the end of Bob's turn, answered once the dragon regained its actions
+        app.increment_initiative_order();
+        assert_eq!(app.prompts[0].kind, PromptKind::LegendaryActions(dragon, 1));
+        app.prompts[0].input = Input::new(String::from("2"));
+        app.submit_prompt();
+        assert_eq!(app.prompts[0].error.as_deref(), Some("Only 1 left"));
+        app.prompts[0].input = Input::new(String::from("1"));
+        app.submit_prompt();
+        let actions = app.current_encounter.creatures[2].legendary_actions();
+        assert_eq!(actions.map(|a| a.left), Some(3));
+
+        app.current_encounter.cursor_index = 2;
+        app.spend_selected_legendary_actions();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Dragon cannot take legendary actions on its own turn")
+        );
+        assert_eq!(app.current_panel, Panel::InitiativeTable);
+    }
+
+    #[test]
+    fn initiative_events_are_added_announced_and_removed() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 20, 10, None, None, None));
+        app.add_creature(Creature::new_player("Bob", 20, 10, None, None, None));
+        app.current_encounter.creatures[0].set_initiative(18);
+        app.current_encounter.creatures[1].set_initiative(12);
+
+        app.add_initiative_entry();
+        app.prompts[0].input = Input::new(String::from("15"));
+        app.submit_prompt();
+        assert_eq!(
+            app.prompts[0].error.as_deref(),
+            Some("A reminder needs a description")
+        );
+        app.prompts[0].input = Input::new(String::from("15 regional Fog rolls in"));
+        app.submit_prompt();
+        assert_eq!(app.current_panel, Panel::InitiativeTable);
+
+        app.increment_initiative_order();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Regional effect: Fog rolls in")
+        );
+        app.remove_active_entry();
+        assert!(app.current_encounter.entries.is_empty());
+        assert_eq!(app.current_encounter.active_index(), Some(0));
+    }
+
+    #[test]
+    fn recharge_abilities_are_spent_by_name() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_monster("Dragon", 200, 19, None, None, None));
+        app.editor_state = EditorState::from_creature(&app.current_encounter.creatures[0]);
+        app.editor_state.recharge_input = Input::new(String::from("Fire Breath 5-6, Roar 6"));
+        app.submit_editor();
+        assert_eq!(
+            app.current_encounter.creatures[0]
+                .recharge_abilities()
+                .len(),
+            2
+        );
+
+        app.use_selected_recharge_ability();
+        assert_eq!(app.prompts[0].input.value(), "Fire Breath");
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Dragon uses Fire Breath (recharge 5–6)")
+        );
+
+        app.use_selected_recharge_ability();
+        assert_eq!(app.prompts[0].input.value(), "Roar");
+        app.prompts[0].input = Input::new(String::from("fire breath"));
+        app.submit_prompt();
+        assert_eq!(
+            app.prompts[0].error.as_deref(),
+            Some("Fire Breath is not recharged")
+        );
+    }
+
+    #[test]
+    fn attacks_hit_against_ac_and_crit_on_a_natural_20() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_monster("Ogre", 59, 11, None, None, None));
+        app.add_creature(Creature::new_player("Alice", 30, 15, None, None, None));
+        app.editor_state = EditorState::from_creature(&app.current_encounter.creatures[0]);
+        app.editor_state.attacks_input =
+            Input::new(String::from("Greatclub +6 1d1+4 bludgeoning reach 5 ft"));
+        app.submit_editor();
+        let attack = app.current_encounter.creatures[0].attacks()[0].clone();
+
+        app.resolve_attack(0, 1, &attack, 8).unwrap();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Ogre misses Alice with Greatclub (14 vs AC 15)")
+        );
+        app.resolve_attack(0, 1, &attack, 9).unwrap();
+        assert_eq!(app.current_encounter.creatures[1].hp(), 25);
+        app.resolve_attack(0, 1, &attack, 20).unwrap();
+        assert_eq!(app.current_encounter.creatures[1].hp(), 19);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some(
+                "Ogre crits Alice with Greatclub (natural 20 vs AC 15): Alice takes 6 \
+                 bludgeoning damage and survives with 19 HP"
+            )
+        );
+
+        app.current_encounter.cursor_index = 1;
+        app.attack_selected_creature();
+        let ogre = app.current_encounter.creatures[0].id();
+        let alice = app.current_encounter.creatures[1].id();
+        assert_eq!(app.prompts[0].kind, PromptKind::Attack(ogre, alice));
+        assert_eq!(app.prompts[0].input.value(), "Greatclub");
+    }
+
+    #[test]
+    fn editor_round_trips_save_proficiencies() {
+        let mut app = App::default();
+        app.editor_state.name_input = Input::new(String::from("Mage"));
+        app.editor_state.max_hp_input = Input::new(String::from("40"));
+        app.editor_state.cr_input = Input::new(String::from("6"));
+        app.editor_state.stat_inputs[Ability::Intelligence as usize] =
+            Input::new(String::from("17"));
+        app.editor_state.saves_input = Input::new(String::from("wis, int"));
+        app.submit_editor();
+
+        let mage = &app.current_encounter.creatures[0];
+        assert_eq!(mage.save_modifier(Ability::Intelligence), 6);
+        app.edit_selected_creature();
+        assert_eq!(app.editor_state.saves_input.value(), "INT, WIS");
+        app.editor_state.saves_input = Input::new(String::from("int, luck"));
+        app.submit_editor();
+        assert!(
+            app.editor_state
+                .error(EditorField::SaveProficiencies)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn mass_saves_deal_full_damage_on_failures_and_half_on_successes() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_player("Alice", 30, 10, None, None, None));
+        app.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        app.add_creature(Creature::new_monster("Ogre", 59, 11, None, None, None));
+
+        app.open_mass_save();
+        assert_eq!(app.prompts[0].kind, PromptKind::MassSave);
+        app.prompts[0].input = Input::new(String::from("25 dex"));
+        app.submit_prompt();
+        assert_eq!(app.current_panel, Panel::MassSave);
+
+        app.mass_save.toggle_all(&app.current_encounter);
+        app.mass_save.toggle_selected(&app.current_encounter);
+        app.roll_mass_save();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("2 of 2 fail the DC 25 DEX save")
+        );
+
+        app.edit_mass_save();
+        app.prompts[0].input = Input::new(String::from("1 str"));
+        app.submit_prompt();
+        app.mass_save.toggle_all(&app.current_encounter);
+        app.roll_mass_save();
+        let ogre = app.current_encounter.creatures[2].id();
+        assert_eq!(
+            app.mass_save.roll(ogre).map(|(_, passed)| passed),
+            Some(true)
+        );
+
+        // The rolls stay with their creatures when the order changes
+        app.current_encounter.move_creature_up(2);
+        app.open_mass_save_damage();
+        app.prompts[0].input = Input::new(String::from("13 fire"));
+        app.submit_prompt();
+        assert_eq!(app.current_panel, Panel::MassSave);
+        let hp: Vec<u32> = app
+            .current_encounter
+            .creatures
+            .iter()
+            .map(|c| c.hp())
+            .collect();
+        assert_eq!(hp, [24, 53, 1]);
+    }
+
+    #[test]
+    fn encounters_save_to_and_open_from_files() {
+        let path =
+            std::env::temp_dir().join(format!("intuitive-app-{}.json", uuid::Uuid::new_v4()));
+        let path = path.to_string_lossy().into_owned();
+        let mut app = App::default();
+        app.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+
+        app.save_encounter();
+        app.prompts[0].input = Input::new(path.clone());
+        app.submit_prompt();
+        assert_eq!(
+            app.status_message,
+            Some(format!("Saved the encounter to {}", path))
+        );
+
+        let mut other = App::default();
+        other.open_encounter();
+        other.prompts[0].input = Input::new(String::from("/nowhere/encounter.json"));
+        other.submit_prompt();
+        assert!(other.prompts[0].error.is_some());
+        other.prompts[0].input = Input::new(path.clone());
+        other.submit_prompt();
+        assert!(other.prompts.is_empty());
+        assert_eq!(other.current_encounter.creatures[0].name(), "Goblin");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mass_saves_can_deal_no_damage_on_successes() {
+        let mut app = App::default();
+        app.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        app.add_creature(Creature::new_monster("Ogre", 59, 11, None, None, None));
+
+        app.open_mass_save();
+        app.prompts[0].input = Input::new(String::from("1 wis"));
+        app.submit_prompt();
+        app.mass_save.toggle_all(&app.current_encounter);
+        app.mass_save.toggle_half_on_success();
+        app.roll_mass_save();
+        app.open_mass_save_damage();
+        app.prompts[0].input = Input::new(String::from("2d8 radiant"));
+        app.submit_prompt();
+
+        assert_eq!(app.current_encounter.creatures[0].hp(), 7);
+        assert_eq!(app.current_encounter.creatures[1].hp(), 59);
+        assert_eq!(app.status_message.as_deref(), Some("Nobody takes damage"));
+
+        // The choice is kept for the next mass save
+        app.open_mass_save();
+        assert!(!app.mass_save.half_on_success);
     }
 }
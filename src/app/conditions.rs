@@ -0,0 +1,69 @@
+use crate::model::{Creature, CreatureId, Encounter, Status};
+
+/// The condition picker popup for a single creature.
+///
+/// * `creature`: id of the creature whose conditions are edited.
+/// * `selected`: index into `Status::ALL` of the highlighted condition.
+/// * `grappler`: id of the creature offered as the grappler when `Grappled` is added, `None` if
+///   there is nobody else.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConditionPicker {
+    pub creature: CreatureId,
+    pub selected: usize,
+    pub grappler: Option<CreatureId>,
+}
+
+impl ConditionPicker {
+    /// Open the picker for the creature with id `creature`, offering its current grappler, or
+    /// else the first other creature, as the grappler.
+    pub fn new(encounter: &Encounter, creature: CreatureId) -> Self {
+        let grappler = encounter
+            .creature(creature)
+            .and_then(Creature::grappler)
+            .filter(|&id| encounter.index_of(id).is_some());
+        let mut picker = ConditionPicker {
+            creature,
+            selected: 0,
+            grappler,
+        };
+        if grappler.is_none() {
+            picker.cycle_grappler(encounter, 1);
+        }
+        picker
+    }
+
+    /// Return the highlighted condition.
+    pub fn condition(&self) -> Status {
+        Status::ALL[self.selected]
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % Status::ALL.len();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + Status::ALL.len() - 1) % Status::ALL.len();
+    }
+
+    /// Offer the next (`step` 1) or previous (`step` -1) creature in the encounter as the
+    /// grappler, never the creature itself.
+    pub fn cycle_grappler(&mut self, encounter: &Encounter, step: isize) {
+        let len = encounter.creatures.len() as isize;
+        let start = self
+            .grappler
+            .and_then(|id| encounter.index_of(id))
+            .or_else(|| encounter.index_of(self.creature));
+        self.grappler = None;
+        let Some(start) = start else {
+            return;
+        };
+        for offset in 1..=len {
+            let index = (start as isize + step * offset).rem_euclid(len) as usize;
+            let id = encounter.creatures[index].id();
+            if id != self.creature {
+                self.grappler = Some(id);
+                return;
+            }
+        }
+    }
+}
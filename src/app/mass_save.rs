@@ -0,0 +1,92 @@
+use crate::model::{Ability, Creature, CreatureId, Encounter};
+
+/// A saving throw several creatures make at once, e.g. against a Fireball.
+///
+/// * `dc`, `ability`: the save the creatures make.
+/// * `selected`: ids of the creatures making the save.
+/// * `cursor`: index of the highlighted creature.
+/// * `rolls`: the save total and whether it passed of every creature that rolled, by id.
+/// * `half_on_success`: whether a creature that passed takes half the damage or none of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MassSave {
+    pub dc: u8,
+    pub ability: Ability,
+    pub selected: Vec<CreatureId>,
+    pub cursor: usize,
+    pub rolls: Vec<(CreatureId, i32, bool)>,
+    pub half_on_success: bool,
+}
+
+impl Default for MassSave {
+    fn default() -> Self {
+        MassSave {
+            dc: 15,
+            ability: Ability::Dexterity,
+            selected: Vec::new(),
+            cursor: 0,
+            rolls: Vec::new(),
+            half_on_success: true,
+        }
+    }
+}
+
+impl MassSave {
+    /// Start a save with nobody selected and the cursor on the creature at `cursor`.
+    pub fn new(cursor: usize) -> Self {
+        MassSave {
+            cursor,
+            ..Default::default()
+        }
+    }
+
+    pub fn select_next(&mut self, encounter: &Encounter) {
+        let len = encounter.creatures.len().max(1);
+        self.cursor = (self.cursor + 1) % len;
+    }
+
+    pub fn select_previous(&mut self, encounter: &Encounter) {
+        let len = encounter.creatures.len().max(1);
+        self.cursor = (self.cursor + len - 1) % len;
+    }
+
+    /// Add the highlighted creature to the creatures making the save, or take it out again.
+    pub fn toggle_selected(&mut self, encounter: &Encounter) {
+        let Some(id) = encounter.creatures.get(self.cursor).map(Creature::id) else {
+            return;
+        };
+        match self.selected.iter().position(|&s| s == id) {
+            Some(position) => {
+                self.selected.remove(position);
+            }
+            None => self.selected.push(id),
+        }
+    }
+
+    /// Select every living creature, or nobody if they are all selected already.
+    pub fn toggle_all(&mut self, encounter: &Encounter) {
+        let living: Vec<CreatureId> = encounter
+            .creatures
+            .iter()
+            .filter(|c| c.is_alive())
+            .map(Creature::id)
+            .collect();
+        self.selected = if living.iter().all(|id| self.selected.contains(id)) {
+            Vec::new()
+        } else {
+            living
+        };
+    }
+
+    /// Switch between half and no damage on a success, e.g. for a Fireball or a Sacred Flame.
+    pub fn toggle_half_on_success(&mut self) {
+        self.half_on_success = !self.half_on_success;
+    }
+
+    /// Return the save total of the creature with `id` and whether it passed, if it rolled.
+    pub fn roll(&self, id: CreatureId) -> Option<(i32, bool)> {
+        self.rolls
+            .iter()
+            .find(|(i, ..)| *i == id)
+            .map(|&(_, total, passed)| (total, passed))
+    }
+}
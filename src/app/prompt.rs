@@ -0,0 +1,164 @@
+use tui_input::Input;
+
+use crate::model::{CreatureId, Encounter, Status};
+
+/// A single line of input the app asks the user for, e.g. the initiative of a player.
+///
+/// * `kind`: what is asked for, and for which creature.
+/// * `input`: the text entered so far.
+/// * `error`: why the last submitted value was rejected.
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: Input,
+    pub error: Option<String>,
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind) -> Self {
+        Prompt {
+            kind,
+            input: Input::default(),
+            error: None,
+        }
+    }
+
+    /// Pre-fill the input with `value`.
+    pub fn with_value(mut self, value: String) -> Self {
+        self.input = Input::new(value);
+        self
+    }
+}
+
+/// What a `Prompt` asks for. Creatures are referred to by their id, so a queued prompt still
+/// finds its creature after the encounter is reordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    /// The initiative of a creature, rolled if left empty.
+    Initiative(CreatureId),
+    /// Confirmation before removing a creature from the encounter.
+    RemoveCreature(CreatureId),
+    /// Damage dealt to a creature, as a number or a dice expression.
+    Damage(CreatureId),
+    /// Healing received by a creature, as a number or a dice expression.
+    Heal(CreatureId),
+    /// Temporary hit points granted to a creature, 0 to remove them.
+    TempHp(CreatureId),
+    /// The d20 roll of a death saving throw, rolled if left empty.
+    DeathSave(CreatureId),
+    /// The total of a save repeated to end a status, rolled if left empty.
+    RepeatSave(CreatureId, Status),
+    /// How long a status lasts on a creature.
+    ConditionDuration(CreatureId, Status),
+    /// The save a creature repeats at the end of its turns to end a status.
+    ConditionSave(CreatureId, Status),
+    /// The spell a creature concentrates on, with an optional duration in rounds.
+    Concentration(CreatureId),
+    /// The total of the Constitution save a creature makes to keep concentrating after taking
+    /// damage, against the given DC. Rolled if left empty.
+    ConcentrationSave(CreatureId, u32),
+    /// The number of legendary actions a creature spends out of the given number it has left,
+    /// none if left empty.
+    LegendaryActions(CreatureId, u8),
+    /// The number of legendary resistances a creature has left, one is used if left empty.
+    LegendaryResistances(CreatureId),
+    /// The DC and ability of the save the creatures of the mass save make.
+    MassSave,
+    /// Damage dealt to the creatures of the mass save, full on a failure and half or none on a
+    /// success.
+    MassSaveDamage,
+    /// The name of the recharge ability a monster uses.
+    RechargeAbility(CreatureId),
+    /// The name of the attack one creature makes against another.
+    Attack(CreatureId, CreatureId),
+    /// A lair action, regional effect or reminder to add to the turn order.
+    AddEntry,
+    /// The file to save the encounter to, the default location if left empty.
+    SaveEncounter,
+    /// The file to open an encounter from.
+    OpenEncounter,
+}
+
+impl PromptKind {
+    /// Return the title shown above the prompt input.
+    pub fn title(&self, encounter: &Encounter) -> String {
+        let name = |id: CreatureId| encounter.creature(id).map_or("?", |c| c.name());
+        match self {
+            PromptKind::Initiative(i) => format!("Initiative for {} (empty to roll)", name(*i)),
+            PromptKind::RemoveCreature(i) => format!("Remove {}? (y/n)", name(*i)),
+            PromptKind::Damage(i) => format!("Damage to {} (e.g. 7 or 2d6+3 fire crit)", name(*i)),
+            PromptKind::Heal(i) => format!("Healing for {} (e.g. 7 or 2d4+2)", name(*i)),
+            PromptKind::TempHp(i) => format!("Temp HP for {} (0 to remove)", name(*i)),
+            PromptKind::DeathSave(i) => format!("Death save for {} (d20, empty to roll)", name(*i)),
+            PromptKind::RepeatSave(i, status) => {
+                let save = encounter
+                    .creature(*i)
+                    .and_then(|c| c.status_effects().iter().find(|e| e.status == *status))
+                    .and_then(|e| e.save);
+                match save {
+                    Some(save) => format!(
+                        "{} save for {} vs {} (empty to roll)",
+                        save,
+                        name(*i),
+                        status
+                    ),
+                    None => format!("Save for {} vs {} (empty to roll)", name(*i), status),
+                }
+            }
+            PromptKind::ConditionDuration(i, status) => {
+                format!(
+                    "Duration of {} on {} (rounds, start or end)",
+                    status,
+                    name(*i)
+                )
+            }
+            PromptKind::ConditionSave(i, status) => {
+                format!("Save to end {} on {} (e.g. 13 wis)", status, name(*i))
+            }
+            PromptKind::Concentration(i) => format!(
+                "Concentration of {} (e.g. Bless 10, empty to end)",
+                name(*i)
+            ),
+            PromptKind::ConcentrationSave(i, dc) => {
+                let spell = encounter
+                    .creature(*i)
+                    .and_then(|c| c.concentration())
+                    .map_or("?", |c| &c.spell);
+                format!(
+                    "DC {} CON save for {} to keep {} (empty to roll)",
+                    dc,
+                    name(*i),
+                    spell
+                )
+            }
+            PromptKind::LegendaryActions(i, left) => format!(
+                "Legendary actions of {} ({} left, empty to pass)",
+                name(*i),
+                left
+            ),
+            PromptKind::LegendaryResistances(i) => format!(
+                "Legendary resistances of {} (empty to use one, or number left)",
+                name(*i)
+            ),
+            PromptKind::MassSave => String::from("Mass save (e.g. 15 dex)"),
+            PromptKind::MassSaveDamage => String::from("Damage on a failed save (e.g. 8d6 fire)"),
+            PromptKind::RechargeAbility(i) => format!("Ability {} uses", name(*i)),
+            PromptKind::Attack(attacker, target) => {
+                let attacks = encounter.creature(*attacker).map_or(Vec::new(), |c| {
+                    c.attacks().iter().map(|a| a.name.as_str()).collect()
+                });
+                format!(
+                    "Attack of {} on {} ({})",
+                    name(*attacker),
+                    name(*target),
+                    attacks.join(", ")
+                )
+            }
+            PromptKind::AddEntry => {
+                String::from("Initiative event (e.g. lair, or 10 round 3 The ceiling collapses)")
+            }
+            PromptKind::SaveEncounter => String::from("Save encounter to (empty for the default)"),
+            PromptKind::OpenEncounter => String::from("Open encounter from"),
+        }
+    }
+}
@@ -1,8 +1,9 @@
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, ModifierKeyCode};
+use crossterm::event::{self, Event, KeyCode, KeyEvent};
 use tui_input::backend::crossterm::EventHandler;
 
 use crate::{
     app::{App, EditorField, Panel},
+    model::InitiativeRoll,
     ui::draw_ui,
 };
 
@@ -20,14 +21,27 @@ fn main() -> color_eyre::Result<()> {
 
     loop {
         term.draw(|frame| draw_ui(frame, &mut app))?;
-        if let Ok(e) = event::read() {
-            if let Some(key_event) = e.as_key_event() {
-                match app.current_panel {
-                    Panel::Editor => handle_editor_events(&mut app, &key_event, &e),
-                    Panel::InitiativeTable | Panel::Sidebar => {
-                        if handle_panel_and_sidebar_events(&mut app, &key_event, &e)? {
-                            break;
-                        }
+        if let Ok(e) = event::read()
+            && let Some(key_event) = e.as_key_event()
+        {
+            app.status_message = None;
+            match app.current_panel {
+                Panel::Editor => handle_editor_events(&mut app, &key_event, &e),
+                Panel::Conditions => {
+                    handle_condition_events(&mut app, &key_event);
+                    storage::store_state(&app)?;
+                }
+                Panel::MassSave => {
+                    handle_mass_save_events(&mut app, &key_event);
+                    storage::store_state(&app)?;
+                }
+                Panel::Prompt => {
+                    handle_prompt_events(&mut app, &key_event, &e);
+                    storage::store_state(&app)?;
+                }
+                Panel::InitiativeTable | Panel::Sidebar => {
+                    if handle_panel_and_sidebar_events(&mut app, &key_event)? {
+                        break;
                     }
                 }
             }
@@ -42,21 +56,34 @@ fn main() -> color_eyre::Result<()> {
 fn handle_panel_and_sidebar_events(
     app: &mut App,
     key_event: &KeyEvent,
-    e: &Event,
 ) -> color_eyre::Result<bool> {
     match key_event.code {
         KeyCode::Char('q') => return Ok(true),
+        KeyCode::Char('j') | KeyCode::Down if app.current_panel == Panel::Sidebar => {
+            app.select_next_setting();
+        }
+        KeyCode::Char('k') | KeyCode::Up if app.current_panel == Panel::Sidebar => {
+            app.select_previous_setting();
+        }
+        KeyCode::Char(' ') | KeyCode::Enter if app.current_panel == Panel::Sidebar => {
+            app.toggle_selected_setting();
+            storage::store_state(app)?;
+        }
         KeyCode::Char('j') | KeyCode::Down => {
             app.select_next_row();
-            storage::store_state(&app)?;
+            storage::store_state(app)?;
         }
         KeyCode::Char('k') | KeyCode::Up => {
             app.select_previous_row();
-            storage::store_state(&app)?;
+            storage::store_state(app)?;
         }
         KeyCode::Char(' ') => {
             app.increment_initiative_order();
-            storage::store_state(&app)?;
+            storage::store_state(app)?;
+        }
+        KeyCode::Backspace => {
+            app.decrement_initiative_order();
+            storage::store_state(app)?;
         }
         KeyCode::Tab => {
             app.current_panel = match app.current_panel {
@@ -65,7 +92,59 @@ fn handle_panel_and_sidebar_events(
                 _ => app.current_panel,
             }
         }
-        KeyCode::Char('n') => app.current_panel = Panel::Editor,
+        KeyCode::Char('n') => app.select_panel(Panel::Editor),
+        KeyCode::Char('e') => app.edit_selected_creature(),
+        KeyCode::Char('s') => {
+            app.sort_initiative();
+            storage::store_state(app)?;
+        }
+        KeyCode::Char('r') => {
+            app.roll_initiative(InitiativeRoll::Everyone);
+            storage::store_state(app)?;
+        }
+        KeyCode::Char('R') => {
+            app.roll_initiative(InitiativeRoll::MonstersOnly);
+            storage::store_state(app)?;
+        }
+        KeyCode::Char('i') => app.enter_selected_initiative(),
+        KeyCode::Char('x') => app.remove_selected_creature(),
+        KeyCode::Char('d') => app.damage_selected_creature(),
+        KeyCode::Char('h') => app.heal_selected_creature(),
+        KeyCode::Char('t') => app.grant_selected_temp_hp(),
+        KeyCode::Char('c') => app.open_condition_picker(),
+        KeyCode::Char('C') => app.concentrate_selected_creature(),
+        KeyCode::Char('m') => app.open_mass_save(),
+        KeyCode::Char('l') => app.spend_selected_legendary_actions(),
+        KeyCode::Char('L') => app.use_selected_legendary_resistance(),
+        KeyCode::Char('u') => {
+            app.toggle_selected_reaction();
+            storage::store_state(app)?;
+        }
+        KeyCode::Char('b') => {
+            app.toggle_active_bonus_action();
+            storage::store_state(app)?;
+        }
+        KeyCode::Char('g') => app.use_selected_recharge_ability(),
+        KeyCode::Char('A') => app.attack_selected_creature(),
+        KeyCode::Char('a') => app.add_initiative_entry(),
+        KeyCode::Char('E') => {
+            app.remove_active_entry();
+            storage::store_state(app)?;
+        }
+        KeyCode::Char('y') => {
+            app.duplicate_selected_creature();
+            storage::store_state(app)?;
+        }
+        KeyCode::Char('K') => {
+            app.move_selected_creature_up();
+            storage::store_state(app)?;
+        }
+        KeyCode::Char('J') => {
+            app.move_selected_creature_down();
+            storage::store_state(app)?;
+        }
+        KeyCode::Char('w') => app.save_encounter(),
+        KeyCode::Char('o') => app.open_encounter(),
         _ => return Ok(false),
     };
     Ok(false)
@@ -75,7 +154,7 @@ fn handle_editor_events(app: &mut App, key_event: &KeyEvent, e: &Event) {
     match key_event.code {
         KeyCode::Char('q') | KeyCode::Esc => {
             app.current_panel = Panel::InitiativeTable;
-            // TODO: Clear input states
+            app.editor_state.reset();
         }
         KeyCode::Tab | KeyCode::Down => {
             app.editor_state.next_field();
@@ -86,28 +165,104 @@ fn handle_editor_events(app: &mut App, key_event: &KeyEvent, e: &Event) {
         KeyCode::Enter => {
             app.submit_editor();
         }
-        _ => handle_editor_input_event_delegation(app, &e),
+        _ => handle_editor_input_event_delegation(app, key_event, e),
+    }
+}
+
+fn handle_condition_events(app: &mut App, key_event: &KeyEvent) {
+    match key_event.code {
+        KeyCode::Char('q') | KeyCode::Esc => app.current_panel = Panel::InitiativeTable,
+        KeyCode::Char('j') | KeyCode::Down => app.condition_picker.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => app.condition_picker.select_previous(),
+        KeyCode::Char(' ') | KeyCode::Enter => app.toggle_selected_condition(),
+        KeyCode::Right | KeyCode::Char('l') => app.adjust_selected_condition(1),
+        KeyCode::Left | KeyCode::Char('h') => app.adjust_selected_condition(-1),
+        KeyCode::Char('d') => app.edit_selected_condition_duration(),
+        KeyCode::Char('v') => app.edit_selected_condition_save(),
+        KeyCode::Char('L') => app.link_selected_condition(),
+        _ => (),
+    }
+}
+
+fn handle_mass_save_events(app: &mut App, key_event: &KeyEvent) {
+    match key_event.code {
+        KeyCode::Char('q') | KeyCode::Esc => app.current_panel = Panel::InitiativeTable,
+        KeyCode::Char('j') | KeyCode::Down => app.mass_save.select_next(&app.current_encounter),
+        KeyCode::Char('k') | KeyCode::Up => app.mass_save.select_previous(&app.current_encounter),
+        KeyCode::Char(' ') => app.mass_save.toggle_selected(&app.current_encounter),
+        KeyCode::Char('a') => app.mass_save.toggle_all(&app.current_encounter),
+        KeyCode::Enter => app.roll_mass_save(),
+        KeyCode::Char('v') => app.edit_mass_save(),
+        KeyCode::Char('d') => app.open_mass_save_damage(),
+        KeyCode::Char('h') => app.mass_save.toggle_half_on_success(),
+        _ => (),
+    }
+}
+
+fn handle_prompt_events(app: &mut App, key_event: &KeyEvent, e: &Event) {
+    match key_event.code {
+        KeyCode::Esc => app.close_prompt(),
+        KeyCode::Enter => app.submit_prompt(),
+        _ => {
+            if let Some(prompt) = app.prompts.front_mut() {
+                prompt.input.handle_event(e);
+            }
+        }
     }
 }
 
-fn handle_editor_input_event_delegation(app: &mut App, e: &Event) {
+fn handle_editor_input_event_delegation(app: &mut App, key_event: &KeyEvent, e: &Event) {
     match app.editor_state.active_input {
+        EditorField::Kind => match key_event.code {
+            KeyCode::Left => app.editor_state.previous_kind(),
+            KeyCode::Right | KeyCode::Char(' ') => app.editor_state.next_kind(),
+            _ => {}
+        },
         EditorField::Name => {
-            app.editor_state.name_input.handle_event(&e);
+            app.editor_state.name_input.handle_event(e);
         }
         EditorField::CurrentHP => {
-            app.editor_state.cur_hp_input.handle_event(&e);
+            app.editor_state.cur_hp_input.handle_event(e);
         }
         EditorField::MaxHP => {
-            app.editor_state.max_hp_input.handle_event(&e);
+            app.editor_state.max_hp_input.handle_event(e);
         }
         EditorField::AC => {
-            app.editor_state.ac_input.handle_event(&e);
+            app.editor_state.ac_input.handle_event(e);
         }
         EditorField::CR => {
-            app.editor_state.cr_input.handle_event(&e);
+            app.editor_state.cr_input.handle_event(e);
+        }
+        EditorField::Stat(ability) => {
+            app.editor_state.stat_inputs[ability as usize].handle_event(e);
+        }
+        EditorField::SaveProficiencies => {
+            app.editor_state.saves_input.handle_event(e);
+        }
+        EditorField::Resistances => {
+            app.editor_state.resistances_input.handle_event(e);
+        }
+        EditorField::Vulnerabilities => {
+            app.editor_state.vulnerabilities_input.handle_event(e);
+        }
+        EditorField::Immunities => {
+            app.editor_state.immunities_input.handle_event(e);
+        }
+        EditorField::LegendaryActions => {
+            app.editor_state.legendary_actions_input.handle_event(e);
+        }
+        EditorField::LegendaryResistances => {
+            app.editor_state.legendary_resistances_input.handle_event(e);
+        }
+        EditorField::Recharge => {
+            app.editor_state.recharge_input.handle_event(e);
+        }
+        EditorField::Attacks => {
+            app.editor_state.attacks_input.handle_event(e);
+        }
+        EditorField::Amount => {
+            app.editor_state.amount_input.handle_event(e);
         }
         _ => (),
     }
 }
-fn handle_main_view_key_event(event: &KeyEvent) {}
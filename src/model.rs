@@ -1,7 +1,23 @@
+mod attack;
+mod concentration;
 mod creature;
+mod damage;
+mod dice;
 mod encounter;
+mod initiative_entry;
+mod recharge;
 mod stats;
 mod status;
 
-pub(crate) use creature::Creature;
-pub(crate) use encounter::Encounter;
+pub(crate) use attack::Attack;
+pub(crate) use concentration::Concentration;
+pub(crate) use creature::{Charges, Creature, CreatureId, DamageOutcome, DeathSaveOutcome};
+pub(crate) use damage::{Damage, DamageModifier, DamageType};
+pub(crate) use dice::roll_d20;
+pub(crate) use encounter::{
+    Encounter, EncounterSettings, ExhaustionRules, InitiativeRoll, TieBreak, TurnEvent,
+};
+pub(crate) use initiative_entry::{EntryKind, InitiativeEntry};
+pub(crate) use recharge::RechargeAbility;
+pub(crate) use stats::{Ability, Stats};
+pub(crate) use status::{Duration, Status, StatusEffect, TurnBoundary};
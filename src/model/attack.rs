@@ -0,0 +1,217 @@
+use std::{fmt, str::FromStr};
+
+use dice_parser::{DiceExpr, RollSpec};
+use serde::{Deserialize, Serialize};
+
+use crate::model::damage::{Damage, DamageType};
+
+/// A weapon or spell attack from a stat block, e.g. `Bite +7 2d10+4 piercing reach 10 ft`.
+///
+/// * `name`: the name of the attack.
+/// * `to_hit`: the bonus added to the attack roll.
+/// * `damage`: the damage dice expression, e.g. `2d10+4`.
+/// * `damage_type`: the type of the damage, untyped if `None`.
+/// * `range`: the reach or range of the attack, e.g. `reach 10 ft`, may be empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attack {
+    pub name: String,
+    pub to_hit: i8,
+    pub damage: String,
+    #[serde(default)]
+    pub damage_type: Option<DamageType>,
+    #[serde(default)]
+    pub range: String,
+}
+
+/// The result of an attack roll against a target.
+///
+/// * `d20`: the number the d20 showed.
+/// * `total`: the attack roll with every modifier.
+/// * `hit`: whether the attack hit.
+/// * `critical`: whether the hit was a critical hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttackRoll {
+    pub d20: u8,
+    pub total: i32,
+    pub hit: bool,
+    pub critical: bool,
+}
+
+impl Attack {
+    /// Check the attack with the d20 showing `d20` against armor class `ac`. `bonus` is added to
+    /// the roll on top of `to_hit`, e.g. the exhaustion penalty. A natural 20 always hits and is
+    /// a critical hit, a natural 1 always misses.
+    pub fn check(&self, d20: u8, bonus: i32, ac: u32) -> AttackRoll {
+        let total = i32::from(d20) + i32::from(self.to_hit) + bonus;
+        let critical = d20 >= 20;
+        AttackRoll {
+            d20,
+            total,
+            hit: critical || (d20 > 1 && total >= ac as i32),
+            critical,
+        }
+    }
+
+    /// Roll the damage of the attack, with twice the dice on a critical hit.
+    pub fn roll_damage(&self, critical: bool) -> Result<Damage, String> {
+        let expr = DiceExpr::parse(&self.damage)
+            .map_err(|_| format!("Cannot roll '{}' for {}", self.damage, self.name))?;
+        let expr = if critical { double_dice(expr) } else { expr };
+        let amount = expr
+            .roll()
+            .map(|roll| roll.total.max(0) as u32)
+            .map_err(|_| format!("Cannot roll '{}' for {}", self.damage, self.name))?;
+
+        let damage = match self.damage_type {
+            Some(damage_type) => Damage::typed(amount, damage_type),
+            None => Damage::new(amount),
+        };
+        Ok(if critical { damage.critical() } else { damage })
+    }
+
+    /// Return the attack as listed in the sidebar, e.g. `Bite +7, 2d10+4 piercing, reach 10 ft`.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("{} {:+}", self.name, self.to_hit)];
+        parts.push(match self.damage_type {
+            Some(damage_type) => format!("{} {}", self.damage, damage_type),
+            None => self.damage.clone(),
+        });
+        if !self.range.is_empty() {
+            parts.push(self.range.clone());
+        }
+        parts.join(", ")
+    }
+
+    /// Parse a comma separated list of attacks, e.g. `"Bite +7 2d10+4 piercing, Claw +7 2d6+4"`.
+    pub fn parse_list(s: &str) -> Result<Vec<Attack>, String> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Format `attacks` as a comma separated list, the inverse of `Attack::parse_list`.
+    pub fn format_list(attacks: &[Attack]) -> String {
+        attacks
+            .iter()
+            .map(Attack::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Double the number of every die in `expr`, leaving flat modifiers alone.
+fn double_dice(expr: DiceExpr) -> DiceExpr {
+    match expr {
+        DiceExpr::Sum(a, b) => DiceExpr::Sum(Box::new(double_dice(*a)), Box::new(double_dice(*b))),
+        DiceExpr::Difference(a, b) => {
+            DiceExpr::Difference(Box::new(double_dice(*a)), Box::new(double_dice(*b)))
+        }
+        DiceExpr::Roll(spec) => {
+            DiceExpr::Roll(RollSpec::new(spec.count * 2, spec.sides, spec.keep))
+        }
+        DiceExpr::Literal(value) => DiceExpr::Literal(value),
+    }
+}
+
+impl fmt::Display for Attack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:+} {}", self.name, self.to_hit, self.damage)?;
+        if let Some(damage_type) = self.damage_type {
+            write!(f, " {}", damage_type)?;
+        }
+        if !self.range.is_empty() {
+            write!(f, " {}", self.range)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Attack {
+    type Err = String;
+
+    /// Parse an attack as `name to-hit damage [type] [range]`, e.g.
+    /// `Bite +7 2d10+4 piercing reach 10 ft` or `Longbow +5 1d8+3 piercing 150/600 ft`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Expected e.g. Bite +7 2d10+4 piercing, not '{}'", s.trim());
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let position = words
+            .iter()
+            .position(|w| w.starts_with(['+', '-']) && w.parse::<i8>().is_ok())
+            .filter(|&p| p > 0)
+            .ok_or_else(error)?;
+        let to_hit = words[position].parse::<i8>().map_err(|_| error())?;
+        let damage = words.get(position + 1).ok_or_else(error)?;
+        DiceExpr::parse(damage).map_err(|_| error())?;
+
+        let rest = &words[position + 2..];
+        // Damage type names are at most two words long
+        let (damage_type, range) = [2, 1]
+            .into_iter()
+            .filter(|&n| rest.len() >= n)
+            .find_map(|n| {
+                let damage_type = rest[..n].join(" ").parse::<DamageType>().ok()?;
+                Some((Some(damage_type), &rest[n..]))
+            })
+            .unwrap_or((None, rest));
+
+        Ok(Attack {
+            name: words[..position].join(" "),
+            to_hit,
+            damage: damage.to_string(),
+            damage_type,
+            range: range.join(" "),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Attack;
+    use crate::model::damage::{Damage, DamageType};
+
+    #[test]
+    fn attacks_parse_with_optional_type_and_range() {
+        let bite: Attack = "Bite +7 2d10+4 magical piercing reach 10 ft"
+            .parse()
+            .unwrap();
+        assert_eq!(bite.name, "Bite");
+        assert_eq!(bite.to_hit, 7);
+        assert_eq!(bite.damage_type, Some(DamageType::MagicalPiercing));
+        assert_eq!(bite.range, "reach 10 ft");
+        assert_eq!(
+            bite.describe(),
+            "Bite +7, 2d10+4 magical piercing, reach 10 ft"
+        );
+
+        let attacks = Attack::parse_list("Fire Bolt -1 2d10, Tail Swipe +3 1d6").unwrap();
+        assert_eq!(attacks[0].damage_type, None);
+        assert_eq!(
+            Attack::parse_list(&Attack::format_list(&attacks)),
+            Ok(attacks)
+        );
+        assert!("Bite 2d10+4".parse::<Attack>().is_err());
+        assert!("+7 2d10+4".parse::<Attack>().is_err());
+        assert!("Bite +7 lots".parse::<Attack>().is_err());
+    }
+
+    #[test]
+    fn natural_rolls_decide_hits_and_crits_double_the_dice() {
+        let attack: Attack = "Club +2 1d1+2 bludgeoning".parse().unwrap();
+        assert!(attack.check(13, 0, 15).hit);
+        assert!(!attack.check(13, -2, 15).hit);
+        assert!(!attack.check(1, 0, 2).hit);
+        let crit = attack.check(20, 0, 30);
+        assert!(crit.hit && crit.critical);
+
+        assert_eq!(
+            attack.roll_damage(false),
+            Ok(Damage::typed(3, DamageType::Bludgeoning))
+        );
+        assert_eq!(
+            attack.roll_damage(true),
+            Ok(Damage::typed(4, DamageType::Bludgeoning).critical())
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{creature::CreatureId, status::Duration};
+
+/// A spell a creature keeps up by concentrating on it.
+///
+/// * `spell`: the name of the spell.
+/// * `targets`: the creatures affected by the spell, if it has any.
+/// * `duration`: when the spell ends on its own.
+/// * `skip_turn_end`: the spell started during the turn whose end would count for `duration`,
+///   so that end is not counted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Concentration {
+    pub spell: String,
+    #[serde(default)]
+    pub targets: Vec<CreatureId>,
+    #[serde(default)]
+    pub duration: Duration,
+    #[serde(default)]
+    pub skip_turn_end: bool,
+}
+
+impl Concentration {
+    /// Concentration on `spell` until it is broken or ended by hand.
+    pub fn new(spell: impl Into<String>) -> Self {
+        Concentration {
+            spell: spell.into(),
+            targets: Vec::new(),
+            duration: Duration::Indefinite,
+            skip_turn_end: false,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Return the DC of the Constitution save to keep concentrating after taking `damage`, half
+    /// the damage but at least 10.
+    pub fn save_dc(damage: u32) -> u32 {
+        (damage / 2).max(10)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Concentration;
+
+    #[test]
+    fn save_dc_is_half_the_damage_but_at_least_10() {
+        assert_eq!(Concentration::save_dc(7), 10);
+        assert_eq!(Concentration::save_dc(21), 10);
+        assert_eq!(Concentration::save_dc(22), 11);
+        assert_eq!(Concentration::save_dc(45), 22);
+    }
+}
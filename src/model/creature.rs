@@ -2,26 +2,117 @@ use dice_parser::{DiceExpr, RollSpec};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::model::{stats::Stats, status::Status};
+use crate::model::{
+    attack::Attack,
+    concentration::Concentration,
+    damage::{Damage, DamageModifier, DamageReport, DamageType},
+    recharge::RechargeAbility,
+    stats::{Ability, Stats},
+    status::{self, Status, StatusEffect, TurnBoundary},
+};
 
 pub type CreatureId = Uuid;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DamageOutcome {
     Survived,
     Downed,
     Died,
 }
 
+/// What a death saving throw did for a downed player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathSaveOutcome {
+    Success,
+    Failure,
+    /// The third success, the player is stable at 0 HP.
+    Stabilized,
+    /// A natural 20, the player regains 1 HP.
+    Revived,
+    /// The third failure.
+    Died,
+}
+
+/// Death saving throws of a player, or an important monster, at 0 HP.
+///
+/// * `successes`, `failures`: the saves made since the player went down.
+/// * `stable`: the player made three successes and no longer rolls until damaged again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeathSaves {
+    pub successes: u8,
+    pub failures: u8,
+    pub stable: bool,
+}
+
+/// Uses of an ability a monster regains all at once, e.g. its legendary actions at the start of
+/// its turn.
+///
+/// * `left`: the uses not spent yet.
+/// * `max`: the uses regained, 0 for a monster without the ability.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Charges {
+    pub left: u8,
+    pub max: u8,
+}
+
+impl Charges {
+    /// Charges with all `max` uses left.
+    pub fn new(max: u8) -> Self {
+        Charges { left: max, max }
+    }
+
+    /// Spend `amount` uses, or none and return `false` if fewer are left.
+    pub fn spend(&mut self, amount: u8) -> bool {
+        match self.left.checked_sub(amount) {
+            Some(left) => {
+                self.left = left;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Regain all uses.
+    pub fn restore(&mut self) {
+        self.left = self.max;
+    }
+}
+
+/// What a creature can do once per round besides taking its action.
+///
+/// * `reaction_used`: the reaction was taken, it is regained at the start of the creature's turn.
+/// * `bonus_action_used`: the bonus action was taken on the creature's current turn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnResources {
+    pub reaction_used: bool,
+    pub bonus_action_used: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Creature {
     Player {
         props: CreatureProperties,
         level: u8,
+        #[serde(default)]
+        death_saves: DeathSaves,
     },
+    /// An `important` monster, e.g. a named NPC, follows the player rules for dropping to 0 HP
+    /// instead of dying outright. Legendary monsters regain their `legendary_actions` at the
+    /// start of their turn and their `legendary_resistances` once a day. Spent
+    /// `recharge_abilities` may come back at the start of their turn.
     Monster {
         props: CreatureProperties,
         cr: f64,
+        #[serde(default)]
+        important: bool,
+        #[serde(default)]
+        death_saves: DeathSaves,
+        #[serde(default)]
+        legendary_actions: Charges,
+        #[serde(default)]
+        legendary_resistances: Charges,
+        #[serde(default)]
+        recharge_abilities: Vec<RechargeAbility>,
     },
 }
 
@@ -43,6 +134,7 @@ impl Creature {
                 stats.unwrap_or_default(),
             ),
             level: level.unwrap_or(1),
+            death_saves: DeathSaves::default(),
         }
     }
     pub fn new_monster(
@@ -62,27 +154,134 @@ impl Creature {
                 stats.unwrap_or_default(),
             ),
             cr: cr.unwrap_or(0.0),
+            important: false,
+            death_saves: DeathSaves::default(),
+            legendary_actions: Charges::default(),
+            legendary_resistances: Charges::default(),
+            recharge_abilities: Vec::new(),
         }
     }
 
     pub fn props(&self) -> &CreatureProperties {
         match self {
-            Creature::Player { props: p, level: _ } => p,
-            Creature::Monster { props: p, cr: _ } => p,
+            Creature::Player { props: p, .. } => p,
+            Creature::Monster { props: p, .. } => p,
         }
     }
 
     fn props_mut(&mut self) -> &mut CreatureProperties {
         match self {
-            Creature::Player {
-                props: p_mut,
-                level: _,
-            } => p_mut,
+            Creature::Player { props: p_mut, .. } => p_mut,
+            Creature::Monster { props: p_mut, .. } => p_mut,
+        }
+    }
+
+    /// Overwrite the stat block of the creature (name, hit points, armor class, stats, damage
+    /// defenses, level or challenge rating, importance and legendary uses) with the one of
+    /// `edited`, keeping its combat state such as statuses and initiative. Legendary uses left are
+    /// kept unless their number changed. The creature becomes a player or monster depending on
+    /// `edited`. Hit points edited above 0 wake a downed or dead creature up, see
+    /// `Creature::heal`, and hit points edited down to 0 follow the rules of dropping to 0 HP.
+    pub fn update_details(&mut self, edited: Creature) {
+        let was_up = self.hp() > 0 && self.is_alive();
+        let new = edited.props();
+        let props = self.props_mut();
+        props.name = new.name.clone();
+        props.max_hp = new.max_hp;
+        props.hp = new.hp.min(new.max_hp);
+        props.ac = new.ac;
+        props.stats = new.stats;
+        props.save_proficiencies = new.save_proficiencies.clone();
+        props.attacks = new.attacks.clone();
+        props.resistances = new.resistances.clone();
+        props.vulnerabilities = new.vulnerabilities.clone();
+        props.immunities = new.immunities.clone();
+
+        let props = self.props().clone();
+        let death_saves = self.death_saves().copied().unwrap_or_default();
+        let keep = |current: Option<&Charges>, edited: Charges| match current {
+            Some(current) if current.max == edited.max => *current,
+            _ => edited,
+        };
+        *self = match edited {
+            Creature::Player { level, .. } => Creature::Player {
+                props,
+                level,
+                death_saves,
+            },
             Creature::Monster {
-                props: p_mut,
-                cr: _,
-            } => p_mut,
+                cr,
+                important,
+                legendary_actions,
+                legendary_resistances,
+                mut recharge_abilities,
+                ..
+            } => {
+                // Abilities that did not change stay spent.
+                for ability in &mut recharge_abilities {
+                    ability.available = self
+                        .recharge_abilities()
+                        .iter()
+                        .find(|a| a.name == ability.name && a.recharge == ability.recharge)
+                        .is_none_or(|a| a.available);
+                }
+                Creature::Monster {
+                    props,
+                    cr,
+                    important,
+                    death_saves,
+                    legendary_actions: keep(self.legendary_actions(), legendary_actions),
+                    legendary_resistances: keep(
+                        self.legendary_resistances(),
+                        legendary_resistances,
+                    ),
+                    recharge_abilities,
+                }
+            }
+        };
+        if self.hp() > 0 {
+            if !was_up {
+                self.wake_up();
+            }
+        } else if self.is_alive() && (was_up || self.death_saves().is_none()) {
+            self.drop_to_0_hp();
+        }
+    }
+
+    /// Return a copy of the creature as it would enter a fight: at full health and without any
+    /// statuses. Initiative is kept so the copy acts together with the original.
+    pub fn fresh_copy(&self) -> Creature {
+        let mut copy = self.clone();
+        let props = copy.props_mut();
+        props.id = Uuid::new_v4();
+        props.hp = props.max_hp;
+        props.temp_hp = 0;
+        props.is_dead = false;
+        props.concentration = None;
+        props.turn = TurnResources::default();
+        copy.clear_status();
+        if let Some(saves) = copy.death_saves_mut() {
+            *saves = DeathSaves::default();
+        }
+        if let Creature::Monster {
+            legendary_actions,
+            legendary_resistances,
+            recharge_abilities,
+            ..
+        } = &mut copy
+        {
+            legendary_actions.restore();
+            legendary_resistances.restore();
+            for ability in recharge_abilities {
+                ability.available = true;
+            }
         }
+        copy
+    }
+
+    /// Return the id other creatures refer to this one by, e.g. as the source of a status.
+    pub fn id(&self) -> CreatureId {
+        self.props().id
     }
 
     /// Return the current health of the Creature
@@ -94,6 +293,23 @@ impl Creature {
         &mut self.props_mut().hp
     }
 
+    /// Return the temporary hit points of the Creature
+    pub fn temp_hp(&self) -> u32 {
+        self.props().temp_hp
+    }
+
+    /// Grant `amount` temporary hit points. Temporary hit points do not stack, so the creature
+    /// keeps whichever of its current and the new pool is higher.
+    pub fn grant_temp_hp(&mut self, amount: u32) {
+        let props = self.props_mut();
+        props.temp_hp = props.temp_hp.max(amount);
+    }
+
+    /// Remove all temporary hit points
+    pub fn clear_temp_hp(&mut self) {
+        self.props_mut().temp_hp = 0;
+    }
+
     /// Return the current health of the Creature
     pub fn max_hp(&self) -> u32 {
         self.props().max_hp
@@ -109,6 +325,11 @@ impl Creature {
         &self.props().name[..]
     }
 
+    /// Rename the creature
+    pub fn set_name(&mut self, name: String) {
+        self.props_mut().name = name;
+    }
+
     /// Returns the stats of the `Creature` as `Stats`
     pub fn stats(&self) -> Stats {
         self.props().stats
@@ -132,51 +353,469 @@ impl Creature {
         &mut self.props_mut().initiative
     }
 
-    /// Add `amount` to the creatures `hp` up to `max_hp`.
-    pub fn heal(&mut self, amount: u32) {
+    /// Mark a monster as important, does nothing for players.
+    pub fn set_important(&mut self, value: bool) {
+        if let Creature::Monster { important, .. } = self {
+            *important = value;
+        }
+    }
+
+    /// Give a monster `actions` legendary actions a round and `resistances` legendary resistances
+    /// a day, all of them left. Does nothing for players.
+    pub fn set_legendary(&mut self, actions: u8, resistances: u8) {
+        if let Creature::Monster {
+            legendary_actions,
+            legendary_resistances,
+            ..
+        } = self
+        {
+            *legendary_actions = Charges::new(actions);
+            *legendary_resistances = Charges::new(resistances);
+        }
+    }
+
+    /// Return the legendary actions of a legendary monster, `None` for other creatures.
+    pub fn legendary_actions(&self) -> Option<&Charges> {
+        match self {
+            Creature::Monster {
+                legendary_actions, ..
+            } if legendary_actions.max > 0 => Some(legendary_actions),
+            _ => None,
+        }
+    }
+
+    pub fn legendary_actions_mut(&mut self) -> Option<&mut Charges> {
+        match self {
+            Creature::Monster {
+                legendary_actions, ..
+            } if legendary_actions.max > 0 => Some(legendary_actions),
+            _ => None,
+        }
+    }
+
+    /// Return the legendary resistances of a legendary monster, `None` for other creatures.
+    pub fn legendary_resistances(&self) -> Option<&Charges> {
+        match self {
+            Creature::Monster {
+                legendary_resistances,
+                ..
+            } if legendary_resistances.max > 0 => Some(legendary_resistances),
+            _ => None,
+        }
+    }
+
+    pub fn legendary_resistances_mut(&mut self) -> Option<&mut Charges> {
+        match self {
+            Creature::Monster {
+                legendary_resistances,
+                ..
+            } if legendary_resistances.max > 0 => Some(legendary_resistances),
+            _ => None,
+        }
+    }
+
+    /// Return the abilities of a monster that need to recharge after use, empty for players.
+    pub fn recharge_abilities(&self) -> &[RechargeAbility] {
+        match self {
+            Creature::Monster {
+                recharge_abilities, ..
+            } => recharge_abilities,
+            Creature::Player { .. } => &[],
+        }
+    }
+
+    pub fn recharge_abilities_mut(&mut self) -> &mut [RechargeAbility] {
+        match self {
+            Creature::Monster {
+                recharge_abilities, ..
+            } => recharge_abilities,
+            Creature::Player { .. } => &mut [],
+        }
+    }
+
+    /// Replace the recharge abilities of a monster. Does nothing for players.
+    pub fn set_recharge_abilities(&mut self, abilities: Vec<RechargeAbility>) {
+        if let Creature::Monster {
+            recharge_abilities, ..
+        } = self
+        {
+            *recharge_abilities = abilities;
+        }
+    }
+
+    /// Return `true` if the creature is at 0 HP but not dead.
+    pub fn is_downed(&self) -> bool {
+        self.hp() == 0 && self.is_alive()
+    }
+
+    /// Return the death saving throws of a player or important monster, `None` for other
+    /// monsters.
+    pub fn death_saves(&self) -> Option<&DeathSaves> {
+        match self {
+            Creature::Player { death_saves, .. }
+            | Creature::Monster {
+                important: true,
+                death_saves,
+                ..
+            } => Some(death_saves),
+            Creature::Monster { .. } => None,
+        }
+    }
+
+    fn death_saves_mut(&mut self) -> Option<&mut DeathSaves> {
+        match self {
+            Creature::Player { death_saves, .. }
+            | Creature::Monster {
+                important: true,
+                death_saves,
+                ..
+            } => Some(death_saves),
+            Creature::Monster { .. } => None,
+        }
+    }
+
+    /// Returns `true` if the creature is a downed player, or important monster, who is not
+    /// stable yet.
+    pub fn needs_death_save(&self) -> bool {
+        self.is_downed() && self.death_saves().is_some_and(|saves| !saves.stable)
+    }
+
+    /// Record a death saving throw where the d20 showed `roll` and `bonus` is added to it, e.g.
+    /// the exhaustion penalty. A total of 10 or higher is a success, a natural 1 counts as two
+    /// failures and a natural 20 brings the player back with 1 HP. Three successes stabilise the
+    /// player, three failures kill them.
+    ///
+    /// Returns `None` if the creature does not need a death save.
+    pub fn record_death_save(&mut self, roll: u8, bonus: i32) -> Option<DeathSaveOutcome> {
+        if !self.needs_death_save() {
+            return None;
+        }
+        if roll >= 20 {
+            self.heal(1);
+            return Some(DeathSaveOutcome::Revived);
+        }
+
+        let saves = self.death_saves_mut()?;
+        if i32::from(roll) + bonus >= 10 {
+            saves.successes += 1;
+            if saves.successes >= 3 {
+                *saves = DeathSaves {
+                    stable: true,
+                    ..Default::default()
+                };
+                return Some(DeathSaveOutcome::Stabilized);
+            }
+            Some(DeathSaveOutcome::Success)
+        } else {
+            saves.failures += if roll <= 1 { 2 } else { 1 };
+            if saves.failures >= 3 {
+                *self.is_dead_mut() = true;
+                return Some(DeathSaveOutcome::Died);
+            }
+            Some(DeathSaveOutcome::Failure)
+        }
+    }
+
+    /// Add `amount` to the creatures `hp` up to `max_hp`. Temporary hit points are not restored.
+    /// Healing a downed player resets their death saves and wakes them up, a dead creature cannot
+    /// be healed.
+    ///
+    /// Returns the hit points the creature gained.
+    pub fn heal(&mut self, amount: u32) -> u32 {
+        if self.is_dead() {
+            return 0;
+        }
         if self.hp() == 0 && amount > 0 {
-            *self.is_dead_mut() = false
+            self.wake_up();
+        }
+        let hp = self.hp();
+        *self.hp_mut() = hp.saturating_add(amount).min(self.max_hp());
+        self.hp() - hp
+    }
+
+    /// Bring the creature back from 0 HP: it is alive, its death saves start over and it is no
+    /// longer `Unconscious`.
+    fn wake_up(&mut self) {
+        *self.is_dead_mut() = false;
+        if let Some(saves) = self.death_saves_mut() {
+            *saves = DeathSaves::default();
         }
-        *self.hp_mut() = (self.hp() + amount).min(self.max_hp());
+        self.remove_status(Status::Unconscious);
+    }
+
+    /// Apply the rules of dropping to 0 HP: a player or important monster falls `Unconscious`,
+    /// any other monster dies.
+    fn drop_to_0_hp(&mut self) -> DamageOutcome {
+        if self.death_saves().is_none() {
+            *self.is_dead_mut() = true;
+            DamageOutcome::Died
+        } else {
+            self.add_status(Status::Unconscious);
+            DamageOutcome::Downed
+        }
+    }
+
+    /// Return the proficiency bonus of the creature, +2 up to level or challenge rating 4 and one
+    /// more every 4 levels or challenge ratings after that.
+    pub fn proficiency_bonus(&self) -> i8 {
+        let level_or_cr = self.get_level_or_cr().max(1.0) as i8;
+        2 + (level_or_cr - 1) / 4
+    }
+
+    /// Return the abilities the creature is proficient in saving throws of.
+    pub fn save_proficiencies(&self) -> &[Ability] {
+        &self.props().save_proficiencies
+    }
+
+    /// Replace the abilities the creature is proficient in saving throws of.
+    pub fn set_save_proficiencies(&mut self, abilities: Vec<Ability>) {
+        self.props_mut().save_proficiencies = abilities;
     }
 
-    /// Lower the creatures `hp` by `amount`.
+    /// Return the modifier of a saving throw of `ability`, the ability modifier plus the
+    /// proficiency bonus if the creature is proficient in it.
+    pub fn save_modifier(&self, ability: Ability) -> i8 {
+        let modifier = self.stats().modifier(ability);
+        if self.save_proficiencies().contains(&ability) {
+            modifier + self.proficiency_bonus()
+        } else {
+            modifier
+        }
+    }
+
+    pub fn attacks(&self) -> &[Attack] {
+        &self.props().attacks
+    }
+
+    /// Replace the attacks the creature can make.
+    pub fn set_attacks(&mut self, attacks: Vec<Attack>) {
+        self.props_mut().attacks = attacks;
+    }
+
+    /// Return the reaction and bonus action the creature has taken.
+    pub fn turn_resources(&self) -> TurnResources {
+        self.props().turn
+    }
+
+    /// Mark the reaction as taken, or as not taken if it was. Returns `true` if it is taken now.
+    pub fn toggle_reaction(&mut self) -> bool {
+        let turn = &mut self.props_mut().turn;
+        turn.reaction_used = !turn.reaction_used;
+        turn.reaction_used
+    }
+
+    /// Mark the bonus action as taken, or as not taken if it was. Returns `true` if it is taken
+    /// now.
+    pub fn toggle_bonus_action(&mut self) -> bool {
+        let turn = &mut self.props_mut().turn;
+        turn.bonus_action_used = !turn.bonus_action_used;
+        turn.bonus_action_used
+    }
+
+    /// Regain the reaction and bonus action, at the start of the creature's turn.
+    pub fn reset_turn_resources(&mut self) {
+        self.props_mut().turn = TurnResources::default();
+    }
+
+    /// Replace the damage types the creature takes half, double or no damage from.
+    pub fn set_damage_defenses(
+        &mut self,
+        resistances: Vec<DamageType>,
+        vulnerabilities: Vec<DamageType>,
+        immunities: Vec<DamageType>,
+    ) {
+        let props = self.props_mut();
+        props.resistances = resistances;
+        props.vulnerabilities = vulnerabilities;
+        props.immunities = immunities;
+    }
+
+    /// Return how the damage defenses of the creature modify damage of `damage_type`. Immunity
+    /// wins over everything else, untyped damage is never modified.
+    pub fn damage_modifier(&self, damage_type: Option<DamageType>) -> DamageModifier {
+        let Some(damage_type) = damage_type else {
+            return DamageModifier::Normal;
+        };
+        let props = self.props();
+        let resisted = props.resistances.contains(&damage_type);
+        let vulnerable = props.vulnerabilities.contains(&damage_type);
+
+        if props.immunities.contains(&damage_type) {
+            DamageModifier::Immune
+        } else if resisted && vulnerable {
+            DamageModifier::ResistedAndVulnerable
+        } else if resisted {
+            DamageModifier::Resisted
+        } else if vulnerable {
+            DamageModifier::Vulnerable
+        } else {
+            DamageModifier::Normal
+        }
+    }
+
+    /// Deal `damage` to the creature after applying its immunities, resistances and
+    /// vulnerabilities, see `Creature::lose_hp`.
+    pub fn take_damage(&mut self, damage: Damage) -> DamageReport {
+        let modifier = self.damage_modifier(damage.damage_type);
+        let applied = modifier.apply(damage.amount);
+        DamageReport {
+            dealt: damage.amount,
+            applied,
+            modifier,
+            outcome: self.lose_hp(applied, damage.critical),
+        }
+    }
+
+    /// Lower the creatures `hp` by `amount`. Temporary hit points absorb the damage first, and
+    /// only what is left of `amount` counts below.
     ///
     /// # Returns
-    /// If the creature is a player and `hp - amount >= -max_hp` then returns
-    /// `DamageOutcome::Downed`. If `hp - amount <= -max_hp` returns `DamageOutcome::Died`.
+    /// If the creature is a player and `hp - amount > -max_hp` then returns
+    /// `DamageOutcome::Downed` and the player falls `Unconscious`. If `hp - amount <= -max_hp`
+    /// the damage is massive and returns `DamageOutcome::Died`. A player already at 0 HP also
+    /// fails a death save, and dies on the third failure. Important monsters follow the same
+    /// rules.
     ///
     /// If not a player, returns `DamageOutcome::Died` if `hp - amount <= 0`
     ///
     /// Always returns `DamageOutcome::Survived` if `hp - amount > 0`.
-    pub fn damage(&mut self, amount: u32) -> DamageOutcome {
-        let curr_hp = self.hp() as i32;
-        let delta = curr_hp - (amount as i32);
+    ///
+    /// A `critical` hit on a downed player counts as two failed death saves. A creature that is
+    /// already dead stays dead and always returns `DamageOutcome::Died`.
+    fn lose_hp(&mut self, amount: u32, critical: bool) -> DamageOutcome {
+        if self.is_dead() {
+            return DamageOutcome::Died;
+        }
+        let absorbed = self.temp_hp().min(amount);
+        self.props_mut().temp_hp -= absorbed;
+        let amount = amount - absorbed;
+
+        let was_downed = self.is_downed();
+        // Widened so that no amount of damage can wrap around.
+        let delta = i64::from(self.hp()) - i64::from(amount);
 
         // Set hp to 0 or whatever it is after the damage.
         *self.hp_mut() = delta.max(0) as u32;
 
         if delta > 0 {
-            DamageOutcome::Survived
-        } else if delta <= -(self.max_hp() as i32) {
-            *self.is_dead_mut() = true;
-            DamageOutcome::Died
-        } else {
-            DamageOutcome::Downed
+            return DamageOutcome::Survived;
         }
+        let massive = delta <= -i64::from(self.max_hp());
+        match self.death_saves_mut() {
+            Some(_) if massive => {
+                *self.is_dead_mut() = true;
+                DamageOutcome::Died
+            }
+            Some(saves) if was_downed && amount > 0 => {
+                saves.stable = false;
+                saves.failures += if critical { 2 } else { 1 };
+                if saves.failures >= 3 {
+                    *self.is_dead_mut() = true;
+                    DamageOutcome::Died
+                } else {
+                    DamageOutcome::Downed
+                }
+            }
+            _ => self.drop_to_0_hp(),
+        }
+    }
+
+    /// Return the statuses currently affecting the creature, without their durations.
+    pub fn get_statuses(&self) -> Vec<Status> {
+        self.props().statuses.iter().map(|e| e.status).collect()
     }
 
-    /// Borrow the status vector in a `Creature` variant
-    pub fn get_statuses(&self) -> &Vec<Status> {
+    /// Borrow the status effects of the creature, durations included.
+    pub fn status_effects(&self) -> &[StatusEffect] {
         &self.props().statuses
     }
 
-    /// Add `status` to the end of the vector of Statuses.
+    /// Return the status of the same condition as `condition`, e.g. the `Exhaustion` level the
+    /// creature is at.
+    pub fn find_condition(&self, condition: Status) -> Option<Status> {
+        self.props()
+            .statuses
+            .iter()
+            .map(|e| e.status)
+            .find(|s| s.same_condition(&condition))
+    }
+
+    /// Return the exhaustion level of the creature, 0 if it is not exhausted.
+    pub fn exhaustion(&self) -> u8 {
+        self.get_statuses()
+            .into_iter()
+            .find_map(|s| match s {
+                Status::Exhaustion(level) => Some(level),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /// Set the exhaustion level of the creature, capped at 6. Level 0 removes the exhaustion and
+    /// level 6 kills the creature. Lowering the level from 6 undoes that death if the creature
+    /// still has hit points, as then it died of exhaustion alone.
+    pub fn set_exhaustion(&mut self, level: u8) {
+        let level = level.min(6);
+        let current = self.exhaustion();
+        match (current, level) {
+            (0, 0) => {}
+            (current, 0) => self.remove_status(Status::Exhaustion(current)),
+            _ => self.add_status(Status::Exhaustion(level)),
+        }
+        if level == 6 {
+            *self.is_dead_mut() = true;
+        } else if current == 6 && self.hp() > 0 {
+            *self.is_dead_mut() = false;
+        }
+    }
+
+    /// Return the id of the creature grappling this one, if any.
+    pub fn grappler(&self) -> Option<CreatureId> {
+        self.get_statuses().into_iter().find_map(|s| match s {
+            Status::Grappled(id) => Some(id),
+            _ => None,
+        })
+    }
+
+    /// Returns `true` if the creature has a condition that includes being incapacitated.
+    pub fn is_incapacitated(&self) -> bool {
+        self.get_statuses().iter().any(|s| {
+            matches!(
+                s,
+                Status::Incapacitated
+                    | Status::Paralyzed
+                    | Status::Petrified
+                    | Status::Stunned
+                    | Status::Unconscious
+            )
+        })
+    }
+
+    /// Add `status` to the end of the vector of Statuses. A creature has every condition at most
+    /// once, so a status of the same condition is replaced instead, keeping its duration.
     ///
     /// * `status`: the `Status` to be added.
     pub fn add_status(&mut self, status: Status) {
-        if !self.props().statuses.contains(&status) {
-            self.props_mut().statuses.push(status);
+        let statuses = &mut self.props_mut().statuses;
+        match statuses
+            .iter_mut()
+            .find(|e| e.status.same_condition(&status))
+        {
+            Some(existing) => existing.status = status,
+            None => statuses.push(StatusEffect::new(status)),
+        }
+    }
+
+    /// Add `effect` to the creature, replacing any effect of the same condition so its duration
+    /// starts over.
+    pub fn add_status_effect(&mut self, effect: StatusEffect) {
+        let statuses = &mut self.props_mut().statuses;
+        match statuses
+            .iter_mut()
+            .find(|e| e.status.same_condition(&effect.status))
+        {
+            Some(existing) => *existing = effect,
+            None => statuses.push(effect),
         }
     }
     ///
@@ -186,11 +825,78 @@ impl Creature {
     ///
     ///
     pub fn remove_status(&mut self, status: Status) {
-        if let Some(i) = self.props().statuses.iter().position(|x| x == &status) {
+        if let Some(i) = self
+            .props()
+            .statuses
+            .iter()
+            .position(|x| x.status == status)
+        {
             self.props_mut().statuses.remove(i);
         }
     }
 
+    /// Count down and end the status effects that depend on `boundary` of the turn of the
+    /// creature with id `anchor`, and return the statuses that ended.
+    pub fn expire_effects(&mut self, anchor: CreatureId, boundary: TurnBoundary) -> Vec<Status> {
+        let id = self.id();
+        let mut expired = Vec::new();
+        self.props_mut().statuses.retain_mut(|effect| {
+            let ends = effect
+                .duration
+                .pass(&mut effect.skip_turn_end, id, anchor, boundary);
+            if ends {
+                expired.push(effect.status);
+            }
+            !ends
+        });
+        expired
+    }
+
+    /// Return the spell the creature is concentrating on, if any.
+    pub fn concentration(&self) -> Option<&Concentration> {
+        self.props().concentration.as_ref()
+    }
+
+    /// Return the spell the creature is concentrating on mutably, if any.
+    pub fn concentration_mut(&mut self) -> Option<&mut Concentration> {
+        self.props_mut().concentration.as_mut()
+    }
+
+    /// Start concentrating on `concentration` and return the spell the creature was
+    /// concentrating on before, if any.
+    pub fn set_concentration(&mut self, concentration: Concentration) -> Option<Concentration> {
+        self.props_mut().concentration.replace(concentration)
+    }
+
+    /// Stop concentrating and return the spell the creature was concentrating on, if any.
+    pub fn take_concentration(&mut self) -> Option<Concentration> {
+        self.props_mut().concentration.take()
+    }
+
+    /// Count the duration of the concentration of the creature down for `boundary` of the turn
+    /// of the creature with id `anchor`, and return `true` if it ran out.
+    pub fn concentration_runs_out(&mut self, anchor: CreatureId, boundary: TurnBoundary) -> bool {
+        let id = self.id();
+        self.props_mut()
+            .concentration
+            .as_mut()
+            .is_some_and(|c| c.duration.pass(&mut c.skip_turn_end, id, anchor, boundary))
+    }
+
+    /// Remove the status effects kept up by the concentration of the creature with id `caster`,
+    /// and return the statuses that ended.
+    pub fn remove_concentration_effects(&mut self, caster: CreatureId) -> Vec<Status> {
+        let mut removed = Vec::new();
+        self.props_mut().statuses.retain(|effect| {
+            let linked = effect.concentration && effect.source == Some(caster);
+            if linked {
+                removed.push(effect.status);
+            }
+            !linked
+        });
+        removed
+    }
+
     /// Remove all Statuses from the Creature
     pub fn clear_status(&mut self) {
         self.props_mut().statuses.clear();
@@ -205,7 +911,8 @@ impl Creature {
             // + Dex modifier
             Box::new(DiceExpr::Literal(self.stats().dex_mod().into())),
         );
-        let res = expr.roll().unwrap().total as u8;
+        // A low roll with a negative modifier can go below zero, which initiative cannot.
+        let res = expr.roll().unwrap().total.clamp(0, u8::MAX.into()) as u8;
         *self.initiative_mut() = Some(res);
         res
     }
@@ -217,11 +924,6 @@ impl Creature {
         self.props_mut().initiative = Some(value)
     }
 
-    /// Set the initiative to `None`
-    pub fn clear_initiative(&mut self) {
-        *self.initiative_mut() = None;
-    }
-
     /// Gets the current initiative
     pub fn get_initiative(&self) -> Option<u8> {
         self.props().initiative
@@ -234,50 +936,85 @@ impl Creature {
 
     pub fn get_level_or_cr(&self) -> f64 {
         match self {
-            Creature::Player { props: _, level } => f64::from(*level),
-            Creature::Monster { props: _, cr } => *cr,
+            Creature::Player { level, .. } => f64::from(*level),
+            Creature::Monster { cr, .. } => *cr,
         }
     }
 }
 
 /// Common properties of `Creature` variants
 ///
+/// * `id`: A unique id other creatures refer to this one by
 /// * `name`: A player name, or statblock name, for the Creature
 /// * `hp`: Current health of the Creature
 /// * `max_hp`: Maximum health of the Creature
+/// * `temp_hp`: Temporary hit points, lost before `hp`
 /// * `ac`: Armor Class of creature
 /// * `is_dead`: wether the Creature is dead.
-/// * `statuses`: `Vec<Status>` of all statuses currently affecting the Creature.
+/// * `statuses`: all statuses currently affecting the Creature, with their durations.
+/// * `concentration`: the spell the Creature is concentrating on, if any.
+/// * `save_proficiencies`: the abilities the Creature adds its proficiency bonus to saves of.
+/// * `turn`: the reaction and bonus action the Creature has taken since its turn started.
+/// * `attacks`: the attacks the Creature can make.
+/// * `resistances`, `vulnerabilities`, `immunities`: damage types taking half, double or no damage.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreatureProperties {
+    #[serde(default = "Uuid::new_v4")]
+    pub id: CreatureId,
     pub name: String,
     pub hp: u32,
     pub max_hp: u32,
+    #[serde(default)]
+    pub temp_hp: u32,
     pub ac: u32,
     pub is_dead: bool,
-    pub statuses: Vec<Status>,
+    #[serde(deserialize_with = "status::deserialize_effects")]
+    pub statuses: Vec<StatusEffect>,
+    #[serde(default)]
+    pub concentration: Option<Concentration>,
     pub initiative: Option<u8>,
     pub stats: Stats,
+    #[serde(default)]
+    pub save_proficiencies: Vec<Ability>,
+    #[serde(default)]
+    pub turn: TurnResources,
+    #[serde(default)]
+    pub attacks: Vec<Attack>,
+    #[serde(default)]
+    pub resistances: Vec<DamageType>,
+    #[serde(default)]
+    pub vulnerabilities: Vec<DamageType>,
+    #[serde(default)]
+    pub immunities: Vec<DamageType>,
 }
 
 impl CreatureProperties {
     pub fn new(name: String, cur_hp: u32, max_hp: u32, ac: u32, stats: Stats) -> Self {
         CreatureProperties {
+            id: Uuid::new_v4(),
             name,
             hp: cur_hp.min(max_hp),
             max_hp,
+            temp_hp: 0,
             ac,
             is_dead: cur_hp == 0,
             initiative: None,
             statuses: Vec::new(),
+            concentration: None,
             stats,
+            save_proficiencies: Vec::new(),
+            turn: TurnResources::default(),
+            attacks: Vec::new(),
+            resistances: Vec::new(),
+            vulnerabilities: Vec::new(),
+            immunities: Vec::new(),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::model::Creature;
+    use crate::model::{Creature, damage::DamageModifier};
 
     use super::*;
 
@@ -311,17 +1048,27 @@ mod test {
         player.heal(10);
         assert_eq!(player.hp(), 20); // should not exceed max_hp
 
-        let outcome = player.damage(5);
+        let outcome = player.take_damage(Damage::new(5)).outcome;
         assert_eq!(outcome, DamageOutcome::Survived);
         assert_eq!(player.hp(), 15);
 
-        let outcome = player.damage(30);
+        let outcome = player.take_damage(Damage::new(30)).outcome;
         assert_eq!(outcome, DamageOutcome::Downed);
         assert_eq!(player.hp(), 0);
+        assert!(player.get_statuses().contains(&Status::Unconscious));
+
+        player.heal(1);
+        assert!(!player.get_statuses().contains(&Status::Unconscious));
 
-        let outcome = player.damage(20);
+        // Massive damage kills outright
+        let outcome = player.take_damage(Damage::new(21)).outcome;
         assert_eq!(outcome, DamageOutcome::Died);
         assert!(player.is_dead());
+
+        // The dead stay dead
+        let outcome = player.take_damage(Damage::new(1)).outcome;
+        assert_eq!(outcome, DamageOutcome::Died);
+        assert!(!player.get_statuses().contains(&Status::Unconscious));
     }
 
     #[test]
@@ -360,7 +1107,6 @@ mod test {
         player.set_initiative(15);
         assert_eq!(player.get_initiative().unwrap(), 15);
 
-        player.clear_initiative();
         let new_roll = player.roll_initiative();
         assert!((1..=20).contains(&new_roll));
     }
@@ -395,19 +1141,95 @@ mod test {
         monster.heal(10);
         assert_eq!(monster.hp(), 20); // should not exceed max_hp
 
-        let outcome = monster.damage(5);
+        let outcome = monster.take_damage(Damage::new(5)).outcome;
         assert_eq!(outcome, DamageOutcome::Survived);
         assert_eq!(monster.hp(), 15);
 
-        let outcome = monster.damage(30);
-        assert_eq!(outcome, DamageOutcome::Downed);
-        assert_eq!(monster.hp(), 0);
-
-        let outcome = monster.damage(20);
+        let outcome = monster.take_damage(Damage::new(15)).outcome;
         assert_eq!(outcome, DamageOutcome::Died);
+        assert_eq!(monster.hp(), 0);
         assert!(monster.is_dead());
     }
 
+    #[test]
+    fn test_important_monster_uses_player_rules() {
+        let mut monster = Creature::new_monster("Strahd", 20, 16, None, None, None);
+        monster.set_important(true);
+        assert_eq!(
+            monster.take_damage(Damage::new(25)).outcome,
+            DamageOutcome::Downed
+        );
+        assert!(monster.needs_death_save());
+        assert!(monster.get_statuses().contains(&Status::Unconscious));
+
+        monster.set_important(false);
+        assert_eq!(
+            monster.take_damage(Damage::new(1)).outcome,
+            DamageOutcome::Died
+        );
+    }
+
+    #[test]
+    fn test_save_modifiers_add_proficiency_by_level_or_cr() {
+        let stats = Stats::new(10, 14, 10, 10, 10, 10);
+        let mut player = Creature::new_player("Alice", 20, 14, None, Some(stats), Some(5));
+        player.set_save_proficiencies(vec![Ability::Dexterity]);
+        assert_eq!(player.proficiency_bonus(), 3);
+        assert_eq!(player.save_modifier(Ability::Dexterity), 5);
+        assert_eq!(player.save_modifier(Ability::Wisdom), 0);
+
+        let lich = Creature::new_monster("Lich", 135, 17, None, None, Some(21.0));
+        assert_eq!(lich.proficiency_bonus(), 7);
+        let rat = Creature::new_monster("Rat", 1, 10, None, None, Some(0.125));
+        assert_eq!(rat.proficiency_bonus(), 2);
+    }
+
+    #[test]
+    fn test_legendary_uses_survive_edits_unless_changed() {
+        let mut dragon = Creature::new_monster("Dragon", 200, 19, None, None, Some(17.0));
+        dragon.set_legendary(3, 3);
+        dragon.legendary_actions_mut().unwrap().spend(2);
+        assert!(!dragon.legendary_resistances_mut().unwrap().spend(4));
+        dragon.legendary_resistances_mut().unwrap().spend(1);
+
+        let mut edited = Creature::new_monster("Dragon", 200, 19, None, None, Some(17.0));
+        edited.set_legendary(3, 4);
+        dragon.update_details(edited);
+        assert_eq!(dragon.legendary_actions().unwrap().left, 1);
+        assert_eq!(dragon.legendary_resistances().unwrap().left, 4);
+        assert_eq!(dragon.fresh_copy().legendary_actions().unwrap().left, 3);
+
+        let mut player = Creature::new_player("Alice", 20, 14, None, None, None);
+        player.set_legendary(3, 3);
+        assert!(player.legendary_actions().is_none());
+    }
+
+    #[test]
+    fn test_spent_recharge_abilities_survive_edits_unless_changed() {
+        let mut dragon = Creature::new_monster("Dragon", 200, 19, None, None, Some(17.0));
+        dragon.set_recharge_abilities(vec![
+            RechargeAbility::new("Fire Breath", 5),
+            RechargeAbility::new("Roar", 6),
+        ]);
+        for ability in dragon.recharge_abilities_mut() {
+            ability.available = false;
+        }
+
+        let mut edited = Creature::new_monster("Dragon", 200, 19, None, None, Some(17.0));
+        edited.set_recharge_abilities(vec![
+            RechargeAbility::new("Fire Breath", 5),
+            RechargeAbility::new("Roar", 5),
+        ]);
+        dragon.update_details(edited);
+        let available: Vec<bool> = dragon
+            .recharge_abilities()
+            .iter()
+            .map(|a| a.available)
+            .collect();
+        assert_eq!(available, [false, true]);
+        assert!(dragon.fresh_copy().recharge_abilities()[0].available);
+    }
+
     #[test]
     fn test_statuses_monster() {
         let mut monster = Creature::new_monster("Eve", 10, 10, None, None, None);
@@ -444,8 +1266,246 @@ mod test {
         monster.set_initiative(15);
         assert_eq!(monster.get_initiative().unwrap(), 15);
 
-        monster.clear_initiative();
         let new_roll = monster.roll_initiative();
         assert!((1..=20).contains(&new_roll));
     }
+
+    #[test]
+    fn test_update_details_can_change_kind() {
+        let mut creature = Creature::new_monster("Bandit", 11, 12, Some(4), None, Some(0.125));
+        creature.add_status(Status::Frightened);
+
+        creature.update_details(Creature::new_player(
+            "Bandit",
+            20,
+            14,
+            Some(12),
+            None,
+            Some(2),
+        ));
+
+        assert!(matches!(creature, Creature::Player { level: 2, .. }));
+        assert_eq!(creature.hp(), 12);
+        assert_eq!(creature.max_hp(), 20);
+        assert_eq!(creature.ac(), 14);
+        assert!(creature.get_statuses().contains(&Status::Frightened));
+    }
+
+    #[test]
+    fn test_update_details_applies_the_death_rule_to_hp() {
+        let mut goblin = Creature::new_monster("Goblin", 7, 15, None, None, None);
+        goblin.update_details(Creature::new_monster("Goblin", 7, 15, Some(0), None, None));
+        assert!(goblin.is_dead());
+
+        goblin.update_details(Creature::new_monster("Goblin", 7, 15, Some(3), None, None));
+        assert!(goblin.is_alive());
+        assert_eq!(goblin.hp(), 3);
+
+        let mut boss = Creature::new_monster("Boss", 50, 17, None, None, None);
+        let mut edited = Creature::new_monster("Boss", 50, 17, Some(0), None, None);
+        edited.set_important(true);
+        boss.update_details(edited);
+        assert!(boss.is_downed());
+    }
+
+    #[test]
+    fn test_temp_hp_absorbs_damage_and_does_not_stack() {
+        let mut player = Creature::new_player("Gina", 20, 10, None, None, None);
+        player.grant_temp_hp(5);
+        player.grant_temp_hp(3);
+        assert_eq!(player.temp_hp(), 5);
+
+        assert_eq!(
+            player.take_damage(Damage::new(3)).outcome,
+            DamageOutcome::Survived
+        );
+        assert_eq!((player.hp(), player.temp_hp()), (20, 2));
+
+        player.take_damage(Damage::new(7));
+        assert_eq!((player.hp(), player.temp_hp()), (15, 0));
+
+        player.grant_temp_hp(4);
+        player.heal(10);
+        assert_eq!((player.hp(), player.temp_hp()), (20, 4));
+
+        // Only the damage left after temp HP counts towards instant death
+        player.grant_temp_hp(20);
+        assert_eq!(
+            player.take_damage(Damage::new(55)).outcome,
+            DamageOutcome::Downed
+        );
+    }
+
+    #[test]
+    fn test_typed_damage_respects_defenses() {
+        let mut monster = Creature::new_monster("Wight", 45, 14, None, None, Some(3.0));
+        let props = monster.props_mut();
+        props.resistances = vec![DamageType::Necrotic, DamageType::Slashing];
+        props.vulnerabilities = vec![DamageType::Radiant, DamageType::Slashing];
+        props.immunities = vec![DamageType::Poison];
+
+        let report = monster.take_damage(Damage::typed(9, DamageType::Necrotic));
+        assert_eq!(
+            (report.applied, report.modifier),
+            (4, DamageModifier::Resisted)
+        );
+
+        let report = monster.take_damage(Damage::typed(5, DamageType::Radiant));
+        assert_eq!(
+            (report.applied, report.modifier),
+            (10, DamageModifier::Vulnerable)
+        );
+
+        let report = monster.take_damage(Damage::typed(50, DamageType::Poison));
+        assert_eq!(
+            (report.applied, report.modifier),
+            (0, DamageModifier::Immune)
+        );
+        assert_eq!(report.outcome, DamageOutcome::Survived);
+
+        let report = monster.take_damage(Damage::typed(7, DamageType::Slashing));
+        assert_eq!(report.applied, 6);
+
+        // Magical slashing is not covered by the nonmagical slashing defenses
+        let report = monster.take_damage(Damage::typed(7, DamageType::MagicalSlashing));
+        assert_eq!(
+            (report.applied, report.modifier),
+            (7, DamageModifier::Normal)
+        );
+
+        let report = monster.take_damage(Damage::new(3));
+        assert_eq!(report.applied, 3);
+        assert_eq!(monster.hp(), 45 - 4 - 10 - 6 - 7 - 3);
+    }
+
+    #[test]
+    fn test_death_save_bonus_counts_except_for_a_natural_20() {
+        let mut player = Creature::new_player("Hank", 10, 10, None, None, None);
+        player.take_damage(Damage::new(10));
+        assert_eq!(
+            player.record_death_save(11, -2),
+            Some(DeathSaveOutcome::Failure)
+        );
+        assert_eq!(
+            player.record_death_save(8, 2),
+            Some(DeathSaveOutcome::Success)
+        );
+        assert_eq!(
+            player.record_death_save(20, -6),
+            Some(DeathSaveOutcome::Revived)
+        );
+    }
+
+    #[test]
+    fn test_death_saves() {
+        let mut player = Creature::new_player("Hank", 10, 10, None, None, None);
+        assert_eq!(player.record_death_save(15, 0), None);
+
+        player.take_damage(Damage::new(10));
+        assert!(player.needs_death_save());
+        assert_eq!(
+            player.record_death_save(10, 0),
+            Some(DeathSaveOutcome::Success)
+        );
+        assert_eq!(
+            player.record_death_save(1, 0),
+            Some(DeathSaveOutcome::Failure)
+        );
+        assert_eq!(player.death_saves().unwrap().failures, 2);
+        assert_eq!(
+            player.record_death_save(12, 0),
+            Some(DeathSaveOutcome::Success)
+        );
+        assert_eq!(
+            player.record_death_save(19, 0),
+            Some(DeathSaveOutcome::Stabilized)
+        );
+        assert!(!player.needs_death_save());
+        assert_eq!(
+            *player.death_saves().unwrap(),
+            DeathSaves {
+                successes: 0,
+                failures: 0,
+                stable: true
+            }
+        );
+
+        // Damage at 0 HP ends stability and fails a save, a critical fails two
+        assert_eq!(
+            player.take_damage(Damage::new(1)).outcome,
+            DamageOutcome::Downed
+        );
+        assert!(player.needs_death_save());
+        assert_eq!(
+            player.take_damage(Damage::new(1).critical()).outcome,
+            DamageOutcome::Died
+        );
+        assert!(player.is_dead());
+    }
+
+    #[test]
+    fn test_natural_20_and_healing_reset_death_saves() {
+        let mut player = Creature::new_player("Iris", 10, 10, None, None, None);
+        player.take_damage(Damage::new(10));
+        player.record_death_save(3, 0);
+        assert_eq!(
+            player.record_death_save(20, 0),
+            Some(DeathSaveOutcome::Revived)
+        );
+        assert_eq!(player.hp(), 1);
+        assert_eq!(*player.death_saves().unwrap(), DeathSaves::default());
+
+        player.take_damage(Damage::new(1));
+        player.record_death_save(2, 0);
+        player.heal(4);
+        assert_eq!(*player.death_saves().unwrap(), DeathSaves::default());
+    }
+
+    #[test]
+    fn test_healing_does_not_bring_back_the_dead() {
+        let mut player = Creature::new_player("Iris", 10, 10, None, None, None);
+        player.take_damage(Damage::new(10));
+        player.record_death_save(1, 0);
+        player.record_death_save(2, 0);
+        assert!(player.is_dead());
+        assert_eq!(player.heal(5), 0);
+        assert!(player.is_dead());
+        assert_eq!(player.hp(), 0);
+
+        let mut goblin = Creature::new_monster("Goblin", 7, 15, None, None, None);
+        goblin.take_damage(Damage::new(7));
+        goblin.heal(7);
+        assert!(goblin.is_dead());
+
+        let mut player = Creature::new_player("Jo", 10, 10, Some(8), None, None);
+        assert_eq!(player.heal(5), 2);
+    }
+
+    #[test]
+    fn test_exhaustion_is_a_single_track() {
+        let mut player = Creature::new_player("Jo", 10, 10, None, None, None);
+        player.add_status(Status::Exhaustion(1));
+        player.add_status(Status::Exhaustion(2));
+        assert_eq!(player.get_statuses(), [Status::Exhaustion(2)]);
+
+        player.set_exhaustion(0);
+        assert_eq!(player.exhaustion(), 0);
+        assert!(player.get_statuses().is_empty());
+
+        player.set_exhaustion(9);
+        assert_eq!(player.exhaustion(), 6);
+        assert!(player.is_dead());
+
+        player.set_exhaustion(5);
+        assert!(player.is_alive());
+    }
+
+    #[test]
+    fn test_lowering_exhaustion_does_not_undo_other_deaths() {
+        let mut goblin = Creature::new_monster("Goblin", 7, 15, None, None, None);
+        goblin.take_damage(Damage::new(7));
+        goblin.set_exhaustion(6);
+        goblin.set_exhaustion(5);
+        assert!(goblin.is_dead());
+    }
 }
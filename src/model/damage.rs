@@ -0,0 +1,220 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::creature::DamageOutcome;
+
+/// A type of damage.
+///
+/// Plain bludgeoning, piercing and slashing are the nonmagical kinds, the `Magical` variants are
+/// dealt by magical attacks. A creature resistant to "slashing from nonmagical attacks" therefore
+/// lists `Slashing`, one resistant to all slashing lists both `Slashing` and `MagicalSlashing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DamageType {
+    Acid,
+    Bludgeoning,
+    Cold,
+    Fire,
+    Force,
+    Lightning,
+    Necrotic,
+    Piercing,
+    Poison,
+    Psychic,
+    Radiant,
+    Slashing,
+    Thunder,
+    MagicalBludgeoning,
+    MagicalPiercing,
+    MagicalSlashing,
+}
+
+impl DamageType {
+    pub const ALL: [DamageType; 16] = [
+        DamageType::Acid,
+        DamageType::Bludgeoning,
+        DamageType::Cold,
+        DamageType::Fire,
+        DamageType::Force,
+        DamageType::Lightning,
+        DamageType::Necrotic,
+        DamageType::Piercing,
+        DamageType::Poison,
+        DamageType::Psychic,
+        DamageType::Radiant,
+        DamageType::Slashing,
+        DamageType::Thunder,
+        DamageType::MagicalBludgeoning,
+        DamageType::MagicalPiercing,
+        DamageType::MagicalSlashing,
+    ];
+
+    /// Return the lowercase name used in stat blocks, e.g. `"magical slashing"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DamageType::Acid => "acid",
+            DamageType::Bludgeoning => "bludgeoning",
+            DamageType::Cold => "cold",
+            DamageType::Fire => "fire",
+            DamageType::Force => "force",
+            DamageType::Lightning => "lightning",
+            DamageType::Necrotic => "necrotic",
+            DamageType::Piercing => "piercing",
+            DamageType::Poison => "poison",
+            DamageType::Psychic => "psychic",
+            DamageType::Radiant => "radiant",
+            DamageType::Slashing => "slashing",
+            DamageType::Thunder => "thunder",
+            DamageType::MagicalBludgeoning => "magical bludgeoning",
+            DamageType::MagicalPiercing => "magical piercing",
+            DamageType::MagicalSlashing => "magical slashing",
+        }
+    }
+
+    /// Parse a comma separated list of damage types, e.g. `"fire, magical slashing"`.
+    pub fn parse_list(s: &str) -> Result<Vec<DamageType>, String> {
+        let mut types = Vec::new();
+        for name in s.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let damage_type = name.parse()?;
+            if !types.contains(&damage_type) {
+                types.push(damage_type);
+            }
+        }
+        Ok(types)
+    }
+
+    /// Format `types` as a comma separated list, the inverse of `DamageType::parse_list`.
+    pub fn format_list(types: &[DamageType]) -> String {
+        types
+            .iter()
+            .map(DamageType::name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Display for DamageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DamageType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        DamageType::ALL
+            .into_iter()
+            .find(|t| t.name() == name)
+            .ok_or_else(|| format!("Unknown damage type '{}'", s.trim()))
+    }
+}
+
+/// An amount of damage dealt to a creature in one go.
+///
+/// * `amount`: the damage rolled, before resistances and the like.
+/// * `damage_type`: the type of the damage, untyped damage is never modified.
+/// * `critical`: whether the damage comes from a critical hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Damage {
+    pub amount: u32,
+    pub damage_type: Option<DamageType>,
+    pub critical: bool,
+}
+
+impl Damage {
+    /// Untyped damage, e.g. from a fall the DM does not bother typing.
+    pub fn new(amount: u32) -> Self {
+        Damage {
+            amount,
+            damage_type: None,
+            critical: false,
+        }
+    }
+
+    pub fn typed(amount: u32, damage_type: DamageType) -> Self {
+        Damage {
+            amount,
+            damage_type: Some(damage_type),
+            critical: false,
+        }
+    }
+
+    /// Mark the damage as dealt by a critical hit.
+    pub fn critical(mut self) -> Self {
+        self.critical = true;
+        self
+    }
+}
+
+/// How the defenses of a creature changed the damage it took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageModifier {
+    Normal,
+    Immune,
+    Resisted,
+    Vulnerable,
+    /// Halved for the resistance, then doubled for the vulnerability.
+    ResistedAndVulnerable,
+}
+
+impl DamageModifier {
+    /// Apply the modifier to `amount`, rounding down like the rules do.
+    pub fn apply(&self, amount: u32) -> u32 {
+        match self {
+            DamageModifier::Normal => amount,
+            DamageModifier::Immune => 0,
+            DamageModifier::Resisted => amount / 2,
+            DamageModifier::Vulnerable => amount.saturating_mul(2),
+            DamageModifier::ResistedAndVulnerable => amount / 2 * 2,
+        }
+    }
+}
+
+/// What happened when a creature took `Damage`.
+///
+/// * `dealt`: the damage before resistances and the like.
+/// * `applied`: the damage the creature actually took, temporary hit points included.
+/// * `modifier`: why `applied` differs from `dealt`.
+/// * `outcome`: the state the creature is left in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamageReport {
+    pub dealt: u32,
+    pub applied: u32,
+    pub modifier: DamageModifier,
+    pub outcome: DamageOutcome,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DamageModifier, DamageType};
+
+    #[test]
+    fn damage_types_parse_from_names() {
+        assert_eq!("Fire".parse(), Ok(DamageType::Fire));
+        assert_eq!(
+            " magical   slashing ".parse(),
+            Ok(DamageType::MagicalSlashing)
+        );
+        assert!("sharp".parse::<DamageType>().is_err());
+        assert_eq!(
+            DamageType::parse_list("cold, poison,cold"),
+            Ok(vec![DamageType::Cold, DamageType::Poison])
+        );
+        assert_eq!(DamageType::parse_list(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn modifiers_round_down() {
+        assert_eq!(DamageModifier::Resisted.apply(7), 3);
+        assert_eq!(DamageModifier::Vulnerable.apply(7), 14);
+        assert_eq!(DamageModifier::ResistedAndVulnerable.apply(7), 6);
+        assert_eq!(DamageModifier::Immune.apply(7), 0);
+        assert_eq!(DamageModifier::Vulnerable.apply(u32::MAX), u32::MAX);
+    }
+}
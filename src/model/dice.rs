@@ -0,0 +1,17 @@
+use dice_parser::{DiceExpr, RollSpec};
+
+/// Roll a single d20.
+pub fn roll_d20() -> u8 {
+    roll_die(20)
+}
+
+/// Roll a single d6, e.g. to recharge an ability.
+pub fn roll_d6() -> u8 {
+    roll_die(6)
+}
+
+fn roll_die(sides: u32) -> u8 {
+    DiceExpr::Roll(RollSpec::new(1, sides, None))
+        .roll()
+        .map_or(1, |roll| roll.total as u8)
+}
@@ -1,6 +1,46 @@
+use std::cmp::Ordering;
+
 use serde::{Deserialize, Serialize};
 
-use crate::model::Creature;
+use crate::model::{
+    Creature,
+    concentration::Concentration,
+    creature::CreatureId,
+    dice::roll_d6,
+    initiative_entry::InitiativeEntry,
+    status::{RepeatSave, Status, StatusEffect, TurnBoundary},
+};
+
+/// Something that happened to a status effect when the turn passed. Creatures are referred to
+/// by their id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TurnEvent {
+    /// `status` ran out on the creature.
+    Expired {
+        creature: CreatureId,
+        status: Status,
+    },
+    /// The creature ended its turn and repeats `save` to end `status`.
+    SaveDue {
+        creature: CreatureId,
+        status: Status,
+        save: RepeatSave,
+    },
+    /// The concentration of the creature on `spell` ran out, ending the effects it kept up.
+    ConcentrationEnded { creature: CreatureId, spell: String },
+    /// The turn of another creature ended and the legendary creature can spend the `left`
+    /// legendary actions it has then, before it regains them if its own turn is next.
+    LegendaryActionsDue { creature: CreatureId, left: u8 },
+    /// The turn passed to the initiative entry at `entry`.
+    EntryReached { entry: usize },
+    /// The turn of a monster with a spent recharge ability started and `roll` was rolled for it.
+    RechargeRolled {
+        creature: CreatureId,
+        ability: String,
+        roll: u8,
+        recharged: bool,
+    },
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Encounter {
@@ -8,6 +48,22 @@ pub struct Encounter {
     pub creatures: Vec<Creature>,
     pub initiative_index: usize,
     pub cursor_index: usize,
+    /// The current round, starting at 1.
+    #[serde(default = "first_round")]
+    pub round: u32,
+    #[serde(default)]
+    pub settings: EncounterSettings,
+    /// Lair actions and other things that take a turn without being a creature.
+    #[serde(default)]
+    pub entries: Vec<InitiativeEntry>,
+    /// Index into `entries` of the entry whose turn it is. The turn is on the entry instead of
+    /// the creature at `initiative_index` while this is set.
+    #[serde(default)]
+    pub active_entry: Option<usize>,
+}
+
+fn first_round() -> u32 {
+    1
 }
 
 impl Default for Encounter {
@@ -17,6 +73,101 @@ impl Default for Encounter {
             creatures: vec![],
             initiative_index: 0,
             cursor_index: 0,
+            round: first_round(),
+            settings: EncounterSettings::default(),
+            entries: Vec::new(),
+            active_entry: None,
+        }
+    }
+}
+
+/// Rules the encounter is run with.
+///
+/// * `tie_break`: how creatures with equal initiative and dexterity are ordered.
+/// * `skip_dead`: whether dead creatures are passed over when advancing the turn.
+/// * `skip_downed`: whether stable players at 0 HP are passed over when advancing the turn. Players
+///   who still make death saves always get their turn.
+/// * `exhaustion`: which edition of the exhaustion rules applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncounterSettings {
+    pub tie_break: TieBreak,
+    pub skip_dead: bool,
+    pub skip_downed: bool,
+    pub exhaustion: ExhaustionRules,
+}
+
+impl Default for EncounterSettings {
+    fn default() -> Self {
+        EncounterSettings {
+            tie_break: TieBreak::default(),
+            skip_dead: true,
+            skip_downed: false,
+            exhaustion: ExhaustionRules::default(),
+        }
+    }
+}
+
+/// Which creatures `Encounter::roll_initiative` rolls for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitiativeRoll {
+    /// Every monster, and every player without an initiative yet.
+    Everyone,
+    /// Only monsters, the players roll their own dice.
+    MonstersOnly,
+}
+
+/// The last tie-break between creatures with the same initiative and dexterity modifier.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// Players act before monsters.
+    #[default]
+    PlayersFirst,
+    /// Tied creatures keep the order they currently have in the table.
+    Manual,
+}
+
+/// The edition of the exhaustion rules. Six levels of exhaustion kill a creature in both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExhaustionRules {
+    /// Every level adds an effect on top of the ones before it.
+    #[default]
+    Rules2014,
+    /// Every level is a -2 penalty to d20 tests and 5 feet less speed.
+    Rules2024,
+}
+
+impl ExhaustionRules {
+    /// Return the effects a creature at exhaustion `level` suffers.
+    pub fn effects(&self, level: u8) -> Vec<String> {
+        if level >= 6 {
+            return vec![String::from("Death")];
+        }
+        match self {
+            ExhaustionRules::Rules2014 => [
+                "Disadvantage on ability checks",
+                "Speed halved",
+                "Disadvantage on attacks and saves",
+                "Hit point maximum halved",
+                "Speed reduced to 0",
+            ]
+            .iter()
+            .take(level as usize)
+            .map(|e| e.to_string())
+            .collect(),
+            ExhaustionRules::Rules2024 if level > 0 => vec![
+                format!("{} to d20 tests", self.d20_penalty(level)),
+                format!("Speed reduced by {} ft", 5 * u32::from(level)),
+            ],
+            ExhaustionRules::Rules2024 => Vec::new(),
+        }
+    }
+
+    /// Return the penalty to d20 tests at exhaustion `level`, 0 under the 2014 rules.
+    pub fn d20_penalty(&self, level: u8) -> i32 {
+        match self {
+            ExhaustionRules::Rules2014 => 0,
+            ExhaustionRules::Rules2024 => -2 * i32::from(level),
         }
     }
 }
@@ -30,6 +181,220 @@ impl Encounter {
         }
     }
 
+    /// Return the number to give the next copy of `name`, one past the highest `"{name} {n}"`
+    /// already in the encounter. A creature called just `name` counts as the first copy.
+    pub fn next_copy_number(&self, name: &str) -> usize {
+        self.creatures
+            .iter()
+            .filter_map(|c| match c.name().strip_prefix(name)? {
+                "" => Some(1),
+                number => number.strip_prefix(' ')?.parse().ok(),
+            })
+            .max()
+            .map_or(1, |n: usize| n + 1)
+    }
+
+    /// Remove and return the creature at `index`. If it was its turn, the turn passes on like in
+    /// `Encounter::select_next_initiative`, to an entry due next or the next creature that is not
+    /// skipped, and what happened to status effects at the start of that turn is returned too.
+    /// Otherwise both the initiative and the cursor stay on the creatures they were on.
+    pub fn remove_creature(&mut self, index: usize) -> Option<(Creature, Vec<TurnEvent>)> {
+        if index >= self.creatures.len() {
+            return None;
+        }
+        let had_turn = index == self.initiative_index && self.active_entry.is_none();
+        let creature = self.creatures.remove(index);
+        let len = self.creatures.len();
+
+        if index < self.cursor_index {
+            self.cursor_index -= 1;
+        }
+        self.cursor_index = self.cursor_index.min(len.saturating_sub(1));
+
+        self.remove_concentration_effects(creature.id());
+        self.end_broken_grapples();
+
+        let mut events = Vec::new();
+        if index < self.initiative_index {
+            self.initiative_index -= 1;
+        } else if had_turn && self.can_pass_turn() {
+            events = self.pass_turn_to(index, None);
+        } else if index == self.initiative_index && index == len {
+            self.initiative_index = 0;
+            if len > 0 {
+                self.round += 1;
+            }
+        }
+        Some((creature, events))
+    }
+
+    /// Return the creature with `id`.
+    pub fn creature(&self, id: CreatureId) -> Option<&Creature> {
+        self.creatures.iter().find(|c| c.id() == id)
+    }
+
+    /// Return the creature with `id` mutably.
+    pub fn creature_mut(&mut self, id: CreatureId) -> Option<&mut Creature> {
+        self.creatures.iter_mut().find(|c| c.id() == id)
+    }
+
+    /// Return the index of the creature with `id`.
+    pub fn index_of(&self, id: CreatureId) -> Option<usize> {
+        self.creatures.iter().position(|c| c.id() == id)
+    }
+
+    /// End `Grappled` on every creature whose grappler left the encounter, died or became
+    /// incapacitated, and return the indices of the creatures set free.
+    pub fn end_broken_grapples(&mut self) -> Vec<usize> {
+        let broken: Vec<(usize, CreatureId)> = self
+            .creatures
+            .iter()
+            .enumerate()
+            .filter_map(|(i, creature)| Some((i, creature.grappler()?)))
+            .filter(|(_, id)| {
+                self.creature(*id)
+                    .is_none_or(|g| g.is_dead() || g.is_incapacitated())
+            })
+            .collect();
+
+        for (i, grappler) in &broken {
+            self.creatures[*i].remove_status(Status::Grappled(*grappler));
+        }
+        broken.into_iter().map(|(i, _)| i).collect()
+    }
+
+    /// Make the creature at `index` concentrate on `concentration`, ending the spell it was
+    /// concentrating on before, which is returned. Like for `add_status_effect`, the end of the
+    /// turn in progress does not count for the duration.
+    pub fn start_concentration(
+        &mut self,
+        index: usize,
+        mut concentration: Concentration,
+    ) -> Option<Concentration> {
+        let caster = self.creatures.get(index).map(Creature::id)?;
+        let ended = self.end_concentration(index);
+        let counted_turn = concentration.duration.counted_turn(caster);
+        concentration.skip_turn_end =
+            counted_turn.is_some() && counted_turn == self.active_creature().map(Creature::id);
+        self.creatures[index].set_concentration(concentration);
+        ended
+    }
+
+    /// End the concentration of the creature at `index`, removing the status effects it kept up
+    /// from every creature, and return the spell that ended.
+    pub fn end_concentration(&mut self, index: usize) -> Option<Concentration> {
+        let creature = self.creatures.get_mut(index)?;
+        let caster = creature.id();
+        let concentration = creature.take_concentration()?;
+        self.remove_concentration_effects(caster);
+        Some(concentration)
+    }
+
+    fn remove_concentration_effects(&mut self, caster: CreatureId) {
+        for creature in self.creatures.iter_mut() {
+            creature.remove_concentration_effects(caster);
+        }
+    }
+
+    /// Make the effect of `status` on the creature at `target` end with the concentration of the
+    /// creature at `caster`, and count `target` among the targets of the spell. Returns `false` if
+    /// the caster is not concentrating or the target does not have `status`.
+    pub fn link_to_concentration(&mut self, caster: usize, target: usize, status: Status) -> bool {
+        let Some(caster_id) = self
+            .creatures
+            .get(caster)
+            .filter(|c| c.concentration().is_some())
+            .map(Creature::id)
+        else {
+            return false;
+        };
+        let Some(creature) = self.creatures.get_mut(target) else {
+            return false;
+        };
+        let Some(effect) = creature
+            .status_effects()
+            .iter()
+            .find(|e| e.status == status)
+            .copied()
+        else {
+            return false;
+        };
+        creature.add_status_effect(effect.with_concentration(caster_id));
+
+        let target_id = creature.id();
+        if let Some(concentration) = self.creatures[caster].concentration_mut()
+            && !concentration.targets.contains(&target_id)
+        {
+            concentration.targets.push(target_id);
+        }
+        true
+    }
+
+    /// End the concentration of every creature that died or became incapacitated, and return
+    /// their indices with the spells that ended.
+    pub fn end_broken_concentration(&mut self) -> Vec<(usize, Concentration)> {
+        let broken: Vec<usize> = self
+            .creatures
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.concentration().is_some() && (c.is_dead() || c.is_incapacitated()))
+            .map(|(i, _)| i)
+            .collect();
+        broken
+            .into_iter()
+            .filter_map(|i| Some((i, self.end_concentration(i)?)))
+            .collect()
+    }
+
+    /// Insert a fresh copy of the creature at `index` right after it, numbered like the batch
+    /// copies of the editor, and move the cursor to it.
+    pub fn duplicate_creature(&mut self, index: usize) {
+        let Some(original) = self.creatures.get(index) else {
+            return;
+        };
+        let base = match original.name().rsplit_once(' ') {
+            Some((base, number)) if number.parse::<usize>().is_ok() => base,
+            _ => original.name(),
+        };
+        let name = format!("{} {}", base, self.next_copy_number(base));
+
+        let mut copy = original.fresh_copy();
+        copy.set_name(name);
+        self.creatures.insert(index + 1, copy);
+
+        if self.initiative_index > index {
+            self.initiative_index += 1;
+        }
+        self.cursor_index = index + 1;
+    }
+
+    /// Swap the creature at `index` with the one above it, keeping the initiative and the cursor on
+    /// the same creatures.
+    pub fn move_creature_up(&mut self, index: usize) {
+        if index > 0 && index < self.creatures.len() {
+            self.swap_creatures(index - 1, index);
+        }
+    }
+
+    /// Swap the creature at `index` with the one below it, keeping the initiative and the cursor on
+    /// the same creatures.
+    pub fn move_creature_down(&mut self, index: usize) {
+        if index + 1 < self.creatures.len() {
+            self.swap_creatures(index, index + 1);
+        }
+    }
+
+    fn swap_creatures(&mut self, a: usize, b: usize) {
+        self.creatures.swap(a, b);
+        let follow = |i: usize| match i {
+            i if i == a => b,
+            i if i == b => a,
+            i => i,
+        };
+        self.initiative_index = follow(self.initiative_index);
+        self.cursor_index = follow(self.cursor_index);
+    }
+
     pub fn select_next_cursor(&mut self) {
         let len = self.creatures.len();
         if len == 0 {
@@ -50,19 +415,346 @@ impl Encounter {
         }
     }
 
-    pub fn select_next_initiative(&mut self) {
+    /// Roll initiative for the creatures selected by `mode`. Monsters are always re-rolled, while
+    /// initiatives players already have are kept.
+    pub fn roll_initiative(&mut self, mode: InitiativeRoll) {
+        for creature in self.creatures.iter_mut() {
+            let roll = match creature {
+                Creature::Monster { .. } => true,
+                Creature::Player { .. } => {
+                    mode == InitiativeRoll::Everyone && !creature.has_initiative()
+                }
+            };
+            if roll {
+                creature.roll_initiative();
+            }
+        }
+    }
+
+    /// Order the creatures by initiative, highest first. Ties go to the higher dexterity modifier
+    /// and then to `settings.tie_break`. Creatures without initiative are moved to the end in
+    /// their current order. The initiative and the cursor stay on the same creatures.
+    pub fn sort_by_initiative(&mut self) {
+        let mut indexed: Vec<(usize, Creature)> = self.creatures.drain(..).enumerate().collect();
+        indexed.sort_by(|(_, a), (_, b)| self.turn_order(a, b));
+
+        let position_of = |old: usize| indexed.iter().position(|(i, _)| *i == old).unwrap_or(0);
+        self.initiative_index = position_of(self.initiative_index);
+        self.cursor_index = position_of(self.cursor_index);
+        self.creatures = indexed.into_iter().map(|(_, c)| c).collect();
+    }
+
+    fn turn_order(&self, a: &Creature, b: &Creature) -> Ordering {
+        let (a_init, b_init) = match (a.get_initiative(), b.get_initiative()) {
+            (Some(a_init), Some(b_init)) => (a_init, b_init),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => return Ordering::Equal,
+        };
+
+        b_init
+            .cmp(&a_init)
+            .then_with(|| b.stats().dex_mod().cmp(&a.stats().dex_mod()))
+            .then_with(|| match self.settings.tie_break {
+                TieBreak::PlayersFirst => {
+                    let is_monster = |c: &Creature| matches!(c, Creature::Monster { .. });
+                    is_monster(a).cmp(&is_monster(b))
+                }
+                TieBreak::Manual => Ordering::Equal,
+            })
+    }
+
+    /// Return the index of the creature whose turn it is, `None` while it is the turn of an
+    /// initiative entry.
+    pub fn active_index(&self) -> Option<usize> {
+        match self.active_entry {
+            Some(_) => None,
+            None => (self.initiative_index < self.creatures.len()).then_some(self.initiative_index),
+        }
+    }
+
+    /// Return the creature whose turn it is.
+    pub fn active_creature(&self) -> Option<&Creature> {
+        self.creatures.get(self.active_index()?)
+    }
+
+    /// Add `entry` to the turn order.
+    pub fn add_entry(&mut self, entry: InitiativeEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Remove and return the initiative entry at `index`. If it was its turn, the turn goes back
+    /// to the creature before it.
+    pub fn remove_entry(&mut self, index: usize) -> Option<InitiativeEntry> {
+        if index >= self.entries.len() {
+            return None;
+        }
+        self.active_entry = match self.active_entry {
+            Some(active) if active == index => None,
+            Some(active) if active > index => Some(active - 1),
+            active => active,
+        };
+        Some(self.entries.remove(index))
+    }
+
+    /// Return where the entry at `index` takes its turn, as the index of the first creature it
+    /// goes before. That is the first creature in turn order with a lower initiative, or without
+    /// one, and the number of creatures if it goes after everyone.
+    pub fn entry_slot(&self, index: usize) -> usize {
+        let count = self.entries[index].count;
+        self.creatures
+            .iter()
+            .position(|c| c.get_initiative().is_none_or(|init| init < count))
+            .unwrap_or(self.creatures.len())
+    }
+
+    /// Return the next entry taking its turn in `slot` this round, after the entry at `after`
+    /// if given. Entries in the same slot go in order of count, highest first.
+    fn next_entry(&self, slot: usize, after: Option<usize>) -> Option<usize> {
+        let key = |i: usize| (std::cmp::Reverse(self.entries[i].count), i);
+        (0..self.entries.len())
+            .filter(|&i| self.entry_slot(i) == slot && self.entries[i].happens_in(self.round))
+            .filter(|&i| after.is_none_or(|after| key(i) > key(after)))
+            .min_by_key(|&i| key(i))
+    }
+
+    /// Add `effect` to the creature at `index`. If the turn whose end counts for the duration of
+    /// the effect is in progress, that end is not counted, e.g. "until the end of the caster's
+    /// next turn" cast on the caster's turn lasts through their next turn.
+    pub fn add_status_effect(&mut self, index: usize, mut effect: StatusEffect) {
+        let Some(target) = self.creatures.get(index).map(Creature::id) else {
+            return;
+        };
+        let counted_turn = effect.duration.counted_turn(target);
+        effect.skip_turn_end =
+            counted_turn.is_some() && counted_turn == self.active_creature().map(Creature::id);
+        self.creatures[index].add_status_effect(effect);
+    }
+
+    /// Tick the status effects of every creature for `boundary` of the turn of the creature at
+    /// `index`. Saves are due for the effects that are still left at the end of the turn, and the
+    /// creature regains its legendary actions at the start of it.
+    fn pass_turn_boundary(&mut self, index: usize, boundary: TurnBoundary) -> Vec<TurnEvent> {
+        let Some(anchor) = self.creatures.get(index).map(Creature::id) else {
+            return Vec::new();
+        };
+        let mut events = Vec::new();
+        if boundary == TurnBoundary::Start {
+            let creature = &mut self.creatures[index];
+            creature.reset_turn_resources();
+            if let Some(actions) = creature.legendary_actions_mut() {
+                actions.restore();
+            }
+            if creature.is_alive() {
+                for ability in creature.recharge_abilities_mut() {
+                    if !ability.available {
+                        let roll = roll_d6();
+                        events.push(TurnEvent::RechargeRolled {
+                            creature: anchor,
+                            ability: ability.name.clone(),
+                            roll,
+                            recharged: ability.recharge_with(roll),
+                        });
+                    }
+                }
+            }
+        }
+
+        for creature in self.creatures.iter_mut() {
+            let id = creature.id();
+            for status in creature.expire_effects(anchor, boundary) {
+                events.push(TurnEvent::Expired {
+                    creature: id,
+                    status,
+                });
+            }
+        }
+
+        let ran_out: Vec<usize> = self
+            .creatures
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, c)| c.concentration_runs_out(anchor, boundary).then_some(i))
+            .collect();
+        for i in ran_out {
+            if let Some(concentration) = self.end_concentration(i) {
+                events.push(TurnEvent::ConcentrationEnded {
+                    creature: self.creatures[i].id(),
+                    spell: concentration.spell,
+                });
+            }
+        }
+
+        let creature = &self.creatures[index];
+        if boundary == TurnBoundary::End && creature.is_alive() {
+            for effect in creature.status_effects() {
+                if let Some(save) = effect.save {
+                    events.push(TurnEvent::SaveDue {
+                        creature: anchor,
+                        status: effect.status,
+                        save,
+                    });
+                }
+            }
+        }
+        events
+    }
+
+    /// Go back to the first turn of round 1.
+    pub fn restart_rounds(&mut self) {
+        self.round = first_round();
+        self.initiative_index = 0;
+        self.active_entry = None;
+    }
+
+    /// Return `true` if `creature` is passed over when advancing the turn.
+    pub fn skips_turn(&self, creature: &Creature) -> bool {
+        if creature.is_dead() {
+            self.settings.skip_dead
+        } else {
+            self.settings.skip_downed
+                && matches!(creature, Creature::Player { .. })
+                && creature.hp() == 0
+                && !creature.needs_death_save()
+        }
+    }
+
+    /// Pass the turn to the next creature that is not skipped, or to an initiative entry due
+    /// before it, starting a new round after the last creature. Status effects tick at the end of
+    /// the current turn and the start of the next, skipped creatures pass through both at once.
+    /// Every other legendary creature that can act may spend legendary actions at the end of the
+    /// current turn. Nothing happens if every creature is skipped and no entry is still to come.
+    ///
+    /// Returns what happened to status effects along the way.
+    pub fn select_next_initiative(&mut self) -> Vec<TurnEvent> {
+        if self.creatures.is_empty() {
+            self.initiative_index = 0;
+            self.active_entry = None;
+            return Vec::new();
+        }
+        if !self.can_pass_turn() {
+            return Vec::new();
+        }
+
+        let mut events = Vec::new();
+        let (slot, after) = match self.active_entry.take() {
+            Some(entry) if entry < self.entries.len() => (self.entry_slot(entry), Some(entry)),
+            _ => {
+                events = self.pass_turn_boundary(self.initiative_index, TurnBoundary::End);
+                for (i, creature) in self.creatures.iter().enumerate() {
+                    let can_act = creature.is_alive() && !creature.is_incapacitated();
+                    let left = creature.legendary_actions().map_or(0, |a| a.left);
+                    if i != self.initiative_index && can_act && left > 0 {
+                        events.push(TurnEvent::LegendaryActionsDue {
+                            creature: creature.id(),
+                            left,
+                        });
+                    }
+                }
+                (self.initiative_index + 1, None)
+            }
+        };
+        events.extend(self.pass_turn_to(slot, after));
+        events
+    }
+
+    /// Return `true` if there is a creature that is not skipped, or an entry of this round or a
+    /// later one, to pass the turn to.
+    fn can_pass_turn(&self) -> bool {
+        self.creatures.iter().any(|c| !self.skips_turn(c))
+            || self
+                .entries
+                .iter()
+                .any(|e| e.round.is_none_or(|r| r >= self.round))
+    }
+
+    /// Give the turn to the next entry due in `slot`, after the entry at `after` if given, or
+    /// else to the creature in `slot` or the first one after it that is not skipped. Skipped
+    /// creatures pass through the start and end of their turn, into the next round if every
+    /// creature is skipped until an entry comes up.
+    fn pass_turn_to(&mut self, mut slot: usize, mut after: Option<usize>) -> Vec<TurnEvent> {
+        let len = self.creatures.len();
+        let mut events = Vec::new();
+        let mut visited = 0;
+        loop {
+            if let Some(entry) = self.next_entry(slot, after) {
+                self.active_entry = Some(entry);
+                events.push(TurnEvent::EntryReached { entry });
+                return events;
+            }
+            if slot >= len {
+                // Everyone had their turn, the entries before the first creature come next. One-off
+                // entries of past rounds will not come up again.
+                self.round += 1;
+                let round = self.round;
+                self.entries.retain(|e| e.round.is_none_or(|r| r >= round));
+                slot = 0;
+                after = None;
+                continue;
+            }
+
+            self.initiative_index = slot;
+            events.extend(self.pass_turn_boundary(slot, TurnBoundary::Start));
+            visited += 1;
+            let nothing_left = visited >= len && self.entries.is_empty();
+            if nothing_left || !self.skips_turn(&self.creatures[slot]) {
+                return events;
+            }
+            events.extend(self.pass_turn_boundary(slot, TurnBoundary::End));
+            slot += 1;
+            after = None;
+        }
+    }
+
+    /// Give the turn back to the previous creature that is not skipped, going back into the
+    /// previous round before the first one. Initiative entries are passed over, on one the turn
+    /// goes back to the creature before it. Nothing happens on the first turn of round 1.
+    pub fn select_previous_initiative(&mut self) {
         let len = self.creatures.len();
         if len == 0 {
             self.initiative_index = 0;
-        } else {
-            self.initiative_index = (self.initiative_index + 1) % len;
+            self.active_entry = None;
+            return;
+        }
+        if let Some(entry) = self.active_entry.take() {
+            if entry < self.entries.len() && self.entry_slot(entry) == 0 {
+                // The new round started before the entry.
+                self.round = self.round.saturating_sub(1).max(first_round());
+            }
+            return;
+        }
+
+        let start = (self.round, self.initiative_index);
+        loop {
+            if self.initiative_index > 0 {
+                self.initiative_index = self.initiative_index.min(len) - 1;
+            } else if self.round > first_round() {
+                self.round -= 1;
+                self.initiative_index = len - 1;
+            } else {
+                // Nobody acted before this turn, stay where we were.
+                (self.round, self.initiative_index) = start;
+                return;
+            }
+            if !self.skips_turn(&self.creatures[self.initiative_index]) {
+                return;
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::model::{Creature, Encounter};
+    use super::{EncounterSettings, ExhaustionRules, InitiativeRoll, TieBreak, TurnEvent};
+    use crate::model::{
+        Ability, Concentration, Creature, Damage, Encounter, EntryKind, InitiativeEntry,
+        RechargeAbility, Stats,
+        status::{Duration, RepeatSave, Status, StatusEffect, TurnBoundary},
+    };
+
+    fn names(encounter: &Encounter) -> Vec<&str> {
+        encounter.creatures.iter().map(|c| c.name()).collect()
+    }
 
     #[test]
     fn cursor_wraps_in_both_directions() {
@@ -78,4 +770,544 @@ mod tests {
         encounter.select_previous_cursor();
         assert_eq!(encounter.cursor_index, 1);
     }
+
+    #[test]
+    fn copy_numbers_continue_after_existing_copies() {
+        let mut encounter = Encounter::default();
+        assert_eq!(encounter.next_copy_number("Goblin"), 1);
+
+        encounter.add_creature(Creature::new_monster("Goblin 2", 7, 15, None, None, None));
+        encounter.add_creature(Creature::new_monster(
+            "Goblin Boss",
+            21,
+            17,
+            None,
+            None,
+            None,
+        ));
+        encounter.add_creature(Creature::new_monster(
+            "Hobgoblin 5",
+            11,
+            18,
+            None,
+            None,
+            None,
+        ));
+        assert_eq!(encounter.next_copy_number("Goblin"), 3);
+    }
+
+    #[test]
+    fn sort_orders_by_initiative_then_dex_then_players() {
+        let quick = Stats::new(10, 16, 10, 10, 10, 10);
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        encounter.add_creature(Creature::new_monster(
+            "Wolf",
+            11,
+            13,
+            None,
+            Some(quick),
+            None,
+        ));
+        encounter.add_creature(Creature::new_player("Alice", 20, 14, None, None, None));
+        encounter.add_creature(Creature::new_player("Bob", 20, 14, None, None, None));
+        encounter.add_creature(Creature::new_monster("Ogre", 59, 11, None, None, None));
+        for (i, init) in [12, 12, 12, 18].into_iter().enumerate() {
+            encounter.creatures[i].set_initiative(init);
+        }
+
+        encounter.sort_by_initiative();
+        assert_eq!(
+            names(&encounter),
+            ["Bob", "Wolf", "Alice", "Goblin", "Ogre"]
+        );
+
+        encounter.settings.tie_break = TieBreak::Manual;
+        encounter.creatures.swap(2, 3);
+        encounter.sort_by_initiative();
+        assert_eq!(
+            names(&encounter),
+            ["Bob", "Wolf", "Goblin", "Alice", "Ogre"]
+        );
+    }
+
+    #[test]
+    fn sort_keeps_initiative_and_cursor_on_the_same_creature() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_player("Carol", 10, 10, None, None, None));
+        encounter.creatures[0].set_initiative(3);
+        encounter.creatures[1].set_initiative(10);
+        encounter.creatures[2].set_initiative(20);
+        encounter.initiative_index = 1;
+        encounter.cursor_index = 0;
+
+        encounter.sort_by_initiative();
+
+        assert_eq!(names(&encounter), ["Carol", "Bob", "Alice"]);
+        assert_eq!(
+            encounter.creatures[encounter.initiative_index].name(),
+            "Bob"
+        );
+        assert_eq!(encounter.creatures[encounter.cursor_index].name(), "Alice");
+    }
+
+    #[test]
+    fn rolling_initiative_keeps_entered_player_values() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        encounter.creatures[0].set_initiative(30);
+
+        encounter.roll_initiative(InitiativeRoll::MonstersOnly);
+        assert_eq!(encounter.creatures[0].get_initiative(), Some(30));
+        assert!(!encounter.creatures[1].has_initiative());
+        assert!(encounter.creatures[2].has_initiative());
+
+        encounter.roll_initiative(InitiativeRoll::Everyone);
+        assert_eq!(encounter.creatures[0].get_initiative(), Some(30));
+        assert!(encounter.creatures[1].has_initiative());
+    }
+
+    #[test]
+    fn turns_count_rounds_in_both_directions() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+
+        encounter.select_previous_initiative();
+        assert_eq!((encounter.round, encounter.initiative_index), (1, 0));
+
+        encounter.select_next_initiative();
+        encounter.select_next_initiative();
+        assert_eq!((encounter.round, encounter.initiative_index), (2, 0));
+
+        encounter.select_previous_initiative();
+        assert_eq!((encounter.round, encounter.initiative_index), (1, 1));
+        assert_eq!(encounter.active_creature().unwrap().name(), "Bob");
+    }
+
+    #[test]
+    fn advancing_skips_dead_and_optionally_downed_creatures() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        encounter.add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Wolf", 11, 13, None, None, None));
+        encounter.creatures[1].take_damage(Damage::new(20));
+        encounter.creatures[2].take_damage(Damage::new(5));
+        encounter.creatures[2].take_damage(Damage::new(5));
+        assert!(encounter.creatures[1].is_dead());
+        assert!(encounter.creatures[2].is_alive());
+
+        encounter.select_next_initiative();
+        assert_eq!(encounter.active_creature().unwrap().name(), "Bob");
+
+        // Bob still has to roll death saves
+        encounter.settings = EncounterSettings {
+            skip_downed: true,
+            ..Default::default()
+        };
+        encounter.select_previous_initiative();
+        encounter.select_next_initiative();
+        assert_eq!(encounter.active_creature().unwrap().name(), "Bob");
+
+        for _ in 0..3 {
+            encounter.creatures[2].record_death_save(10, 0);
+        }
+        encounter.select_previous_initiative();
+        encounter.select_next_initiative();
+        assert_eq!(encounter.active_creature().unwrap().name(), "Wolf");
+        encounter.select_previous_initiative();
+        assert_eq!(encounter.active_creature().unwrap().name(), "Alice");
+
+        encounter.settings.skip_dead = false;
+        encounter.select_next_initiative();
+        assert_eq!(encounter.active_creature().unwrap().name(), "Goblin");
+    }
+
+    #[test]
+    fn advancing_does_nothing_when_every_creature_is_skipped() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        encounter.add_creature(Creature::new_monster("Wolf", 11, 13, None, None, None));
+        encounter.select_next_initiative();
+        encounter.creatures[0].take_damage(Damage::new(7));
+        encounter.creatures[1].take_damage(Damage::new(11));
+
+        assert!(encounter.select_next_initiative().is_empty());
+        assert_eq!(encounter.round, 1);
+        assert_eq!(encounter.initiative_index, 1);
+    }
+
+    #[test]
+    fn lair_actions_come_up_when_every_creature_is_skipped() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        encounter.add_creature(Creature::new_monster("Wolf", 11, 13, None, None, None));
+        encounter.creatures[0].set_initiative(15);
+        encounter.creatures[1].set_initiative(12);
+        encounter.add_entry(InitiativeEntry::new(EntryKind::Lair, 20, ""));
+        encounter.initiative_index = 1;
+        encounter.creatures[0].take_damage(Damage::new(7));
+        encounter.creatures[1].take_damage(Damage::new(11));
+
+        for round in [2, 3] {
+            assert_eq!(
+                encounter.select_next_initiative(),
+                [TurnEvent::EntryReached { entry: 0 }]
+            );
+            assert_eq!(encounter.round, round);
+            assert_eq!(encounter.active_entry, Some(0));
+        }
+    }
+
+    #[test]
+    fn removing_creatures_keeps_the_current_turn() {
+        let mut encounter = Encounter::default();
+        for name in ["Alice", "Bob", "Carol", "Dave"] {
+            encounter.add_creature(Creature::new_player(name, 10, 10, None, None, None));
+        }
+        encounter.initiative_index = 2;
+        encounter.cursor_index = 3;
+
+        encounter.remove_creature(0);
+        assert_eq!(encounter.active_creature().unwrap().name(), "Carol");
+        assert_eq!(encounter.creatures[encounter.cursor_index].name(), "Dave");
+
+        encounter.remove_creature(1);
+        assert_eq!(encounter.active_creature().unwrap().name(), "Dave");
+
+        encounter.remove_creature(1);
+        assert_eq!(encounter.active_creature().unwrap().name(), "Bob");
+        assert_eq!(encounter.round, 2);
+        assert_eq!(encounter.cursor_index, 0);
+    }
+
+    #[test]
+    fn removing_the_active_creature_starts_the_next_turn() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        encounter.add_creature(Creature::new_monster("Dragon", 200, 19, None, None, None));
+        encounter.creatures[1].take_damage(Damage::new(7));
+        let dragon = &mut encounter.creatures[2];
+        dragon.set_legendary(3, 3);
+        dragon.legendary_actions_mut().unwrap().spend(2);
+        dragon.set_recharge_abilities(vec![RechargeAbility::new("Fire Breath", 5)]);
+        dragon.recharge_abilities_mut()[0].available = false;
+
+        let (alice, events) = encounter.remove_creature(0).unwrap();
+        assert_eq!(alice.name(), "Alice");
+        assert_eq!(encounter.active_creature().unwrap().name(), "Dragon");
+        assert_eq!(encounter.creatures[1].legendary_actions().unwrap().left, 3);
+        assert!(matches!(events[..], [TurnEvent::RechargeRolled { .. }]));
+    }
+
+    #[test]
+    fn duplicating_and_moving_creatures_keeps_the_current_turn() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.creatures[0].take_damage(Damage::new(3));
+        encounter.initiative_index = 1;
+
+        encounter.duplicate_creature(0);
+        assert_eq!(names(&encounter), ["Goblin", "Goblin 2", "Alice"]);
+        assert_eq!(encounter.creatures[1].hp(), 7);
+        assert_eq!(encounter.active_creature().unwrap().name(), "Alice");
+        assert_eq!(encounter.cursor_index, 1);
+
+        encounter.duplicate_creature(1);
+        assert_eq!(encounter.creatures[2].name(), "Goblin 3");
+
+        encounter.move_creature_down(3);
+        encounter.move_creature_up(3);
+        assert_eq!(
+            names(&encounter),
+            ["Goblin", "Goblin 2", "Alice", "Goblin 3"]
+        );
+        assert_eq!(encounter.active_creature().unwrap().name(), "Alice");
+    }
+
+    #[test]
+    fn status_effects_expire_on_turn_boundaries() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        let alice = encounter.creatures[0].id();
+        let goblin = encounter.creatures[1].id();
+
+        // Cast on Alice's turn, so it lasts until the end of her next one
+        let until_end = Duration::UntilTurn {
+            creature: alice,
+            boundary: TurnBoundary::End,
+        };
+        encounter.add_status_effect(
+            1,
+            StatusEffect::new(Status::Stunned).with_duration(until_end),
+        );
+        encounter.add_status_effect(
+            1,
+            StatusEffect::new(Status::Prone).with_duration(Duration::Rounds(1)),
+        );
+        encounter.add_status_effect(
+            1,
+            StatusEffect::new(Status::Paralyzed).with_save(13, Ability::Wisdom),
+        );
+
+        assert!(encounter.select_next_initiative().is_empty());
+        let events = encounter.select_next_initiative();
+        assert_eq!(
+            events,
+            [
+                TurnEvent::Expired {
+                    creature: goblin,
+                    status: Status::Prone
+                },
+                TurnEvent::SaveDue {
+                    creature: goblin,
+                    status: Status::Paralyzed,
+                    save: RepeatSave {
+                        dc: 13,
+                        ability: Ability::Wisdom
+                    }
+                },
+            ]
+        );
+        assert_eq!(
+            encounter.select_next_initiative()[0],
+            TurnEvent::Expired {
+                creature: goblin,
+                status: Status::Stunned
+            }
+        );
+    }
+
+    #[test]
+    fn grapples_end_when_the_grappler_cannot_hold_on() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Ogre", 59, 11, None, None, None));
+        encounter.add_creature(Creature::new_monster("Wolf", 11, 13, None, None, None));
+        let ogre = encounter.creatures[2].id();
+        let wolf = encounter.creatures[3].id();
+        encounter.creatures[0].add_status(Status::Grappled(ogre));
+        encounter.creatures[1].add_status(Status::Grappled(wolf));
+
+        encounter.move_creature_up(2);
+        assert_eq!(encounter.index_of(ogre), Some(1));
+        assert_eq!(encounter.creature(ogre).unwrap().name(), "Ogre");
+        assert!(encounter.end_broken_grapples().is_empty());
+
+        encounter.creatures[1].add_status(Status::Stunned);
+        assert_eq!(encounter.end_broken_grapples(), [0]);
+        assert_eq!(encounter.creatures[0].grappler(), None);
+
+        encounter.remove_creature(3);
+        assert_eq!(encounter.creatures[2].grappler(), None);
+    }
+
+    #[test]
+    fn ending_concentration_ends_the_linked_statuses() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        encounter.add_creature(Creature::new_monster("Ogre", 59, 11, None, None, None));
+        encounter.creatures[1].add_status(Status::Paralyzed);
+        encounter.creatures[1].add_status(Status::Prone);
+        assert!(!encounter.link_to_concentration(0, 1, Status::Paralyzed));
+
+        // Cast on Alice's turn, so it lasts through her next one
+        let hold_person = Concentration::new("Hold Person").with_duration(Duration::Rounds(1));
+        assert_eq!(encounter.start_concentration(0, hold_person), None);
+        assert!(encounter.link_to_concentration(0, 1, Status::Paralyzed));
+        assert!(!encounter.link_to_concentration(0, 2, Status::Paralyzed));
+        assert_eq!(
+            encounter.creatures[0].concentration().unwrap().targets,
+            [encounter.creatures[1].id()]
+        );
+
+        assert!(encounter.select_next_initiative().is_empty());
+        assert!(encounter.select_next_initiative().is_empty());
+        assert!(encounter.select_next_initiative().is_empty());
+        assert_eq!(
+            encounter.select_next_initiative(),
+            [TurnEvent::ConcentrationEnded {
+                creature: encounter.creatures[0].id(),
+                spell: String::from("Hold Person"),
+            }]
+        );
+        assert_eq!(encounter.creatures[0].concentration(), None);
+        assert_eq!(encounter.creatures[1].get_statuses(), [Status::Prone]);
+
+        encounter.start_concentration(2, Concentration::new("Web"));
+        encounter.creatures[1].add_status(Status::Restrained);
+        encounter.link_to_concentration(2, 1, Status::Restrained);
+        encounter.creatures[2].add_status(Status::Stunned);
+        let broken = encounter.end_broken_concentration();
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].0, 2);
+        assert_eq!(encounter.creatures[1].get_statuses(), [Status::Prone]);
+
+        encounter.start_concentration(0, Concentration::new("Bane"));
+        encounter.creatures[1].add_status(Status::Frightened);
+        encounter.link_to_concentration(0, 1, Status::Frightened);
+        encounter.remove_creature(0);
+        assert_eq!(encounter.creatures[0].get_statuses(), [Status::Prone]);
+    }
+
+    #[test]
+    fn legendary_actions_are_due_after_other_turns_and_regained_on_their_own() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Dragon", 200, 19, None, None, None));
+        encounter.add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+        encounter.creatures[1].set_legendary(3, 3);
+
+        assert_eq!(
+            encounter.select_next_initiative(),
+            [TurnEvent::LegendaryActionsDue {
+                creature: encounter.creatures[1].id(),
+                left: 3
+            }]
+        );
+        encounter.creatures[1]
+            .legendary_actions_mut()
+            .unwrap()
+            .spend(3);
+        assert!(encounter.select_next_initiative().is_empty());
+        assert_eq!(encounter.creatures[1].legendary_actions().unwrap().left, 0);
+
+        assert!(encounter.select_next_initiative().is_empty());
+        encounter.creatures[1].add_status(Status::Stunned);
+        assert!(encounter.select_next_initiative().is_empty());
+        assert_eq!(encounter.creatures[1].legendary_actions().unwrap().left, 3);
+    }
+
+    #[test]
+    fn initiative_entries_take_turns_after_ties_and_in_their_round() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Goblin", 7, 15, None, None, None));
+        for (i, init) in [20, 15, 8].into_iter().enumerate() {
+            encounter.creatures[i].set_initiative(init);
+        }
+        encounter.add_entry(InitiativeEntry::new(EntryKind::Lair, 20, ""));
+        encounter.add_entry(InitiativeEntry::new(EntryKind::Reminder, 10, "Collapse").in_round(2));
+
+        // Round 1: Alice, lair, Bob, Goblin, the reminder waits for round 2.
+        assert_eq!(
+            encounter.select_next_initiative(),
+            [TurnEvent::EntryReached { entry: 0 }]
+        );
+        assert!(encounter.active_creature().is_none());
+        encounter.select_previous_initiative();
+        assert_eq!(encounter.active_creature().unwrap().name(), "Alice");
+        encounter.select_next_initiative();
+        encounter.select_next_initiative();
+        assert_eq!(encounter.active_creature().unwrap().name(), "Bob");
+        encounter.select_next_initiative();
+        assert_eq!(encounter.active_creature().unwrap().name(), "Goblin");
+
+        encounter.select_next_initiative();
+        encounter.select_next_initiative();
+        encounter.select_next_initiative();
+        assert_eq!(
+            encounter.select_next_initiative(),
+            [TurnEvent::EntryReached { entry: 1 }]
+        );
+        assert_eq!(encounter.round, 2);
+        encounter.select_next_initiative();
+        assert_eq!(encounter.active_creature().unwrap().name(), "Goblin");
+
+        // The one-off reminder is gone once its round is over.
+        encounter.select_next_initiative();
+        assert_eq!((encounter.round, encounter.entries.len()), (3, 1));
+        assert_eq!(encounter.active_creature().unwrap().name(), "Alice");
+    }
+
+    #[test]
+    fn entries_after_everyone_start_the_next_round_from_the_top() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.creatures[0].set_initiative(12);
+        encounter.add_entry(InitiativeEntry::new(EntryKind::Lair, 20, ""));
+        encounter.add_entry(InitiativeEntry::new(EntryKind::Regional, 5, ""));
+
+        assert_eq!(
+            encounter.select_next_initiative(),
+            [TurnEvent::EntryReached { entry: 1 }]
+        );
+        assert_eq!(encounter.round, 1);
+        assert_eq!(
+            encounter.select_next_initiative(),
+            [TurnEvent::EntryReached { entry: 0 }]
+        );
+        assert_eq!(encounter.round, 2);
+        encounter.select_previous_initiative();
+        assert_eq!((encounter.round, encounter.initiative_index), (1, 0));
+    }
+
+    #[test]
+    fn reactions_are_regained_at_the_start_of_the_own_turn() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_player("Bob", 10, 10, None, None, None));
+        encounter.creatures[0].toggle_bonus_action();
+        assert!(encounter.creatures[1].toggle_reaction());
+
+        encounter.select_next_initiative();
+        assert!(encounter.creatures[0].turn_resources().bonus_action_used);
+        assert_eq!(encounter.creatures[1].turn_resources(), Default::default());
+        encounter.select_next_initiative();
+        assert!(!encounter.creatures[0].turn_resources().bonus_action_used);
+    }
+
+    #[test]
+    fn spent_recharge_abilities_roll_at_the_start_of_the_own_turn() {
+        let mut encounter = Encounter::default();
+        encounter.add_creature(Creature::new_player("Alice", 10, 10, None, None, None));
+        encounter.add_creature(Creature::new_monster("Dragon", 200, 19, None, None, None));
+        encounter.creatures[1].set_recharge_abilities(vec![
+            RechargeAbility::new("Fire Breath", 5),
+            RechargeAbility::new("Roar", 6),
+        ]);
+        encounter.creatures[1].recharge_abilities_mut()[0].available = false;
+
+        let events = encounter.select_next_initiative();
+        let [
+            TurnEvent::RechargeRolled {
+                creature,
+                ability,
+                roll,
+                recharged,
+            },
+        ] = &events[..]
+        else {
+            panic!("expected one recharge roll, got {:?}", events);
+        };
+        assert_eq!(*creature, encounter.creatures[1].id());
+        assert_eq!(ability, "Fire Breath");
+        assert!((1..=6).contains(roll));
+        assert_eq!(*recharged, *roll >= 5);
+        assert!(encounter.select_next_initiative().is_empty());
+    }
+
+    #[test]
+    fn exhaustion_effects_depend_on_the_rules() {
+        let effects = ExhaustionRules::Rules2014.effects(2);
+        assert_eq!(effects, ["Disadvantage on ability checks", "Speed halved"]);
+        assert_eq!(
+            ExhaustionRules::Rules2024.effects(3),
+            ["-6 to d20 tests", "Speed reduced by 15 ft"]
+        );
+        assert_eq!(ExhaustionRules::Rules2014.d20_penalty(3), 0);
+        assert_eq!(ExhaustionRules::Rules2024.effects(6), ["Death"]);
+        assert!(ExhaustionRules::Rules2024.effects(0).is_empty());
+    }
 }
@@ -0,0 +1,139 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// What an `InitiativeEntry` stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntryKind {
+    /// The lair acts, usually on initiative count 20.
+    Lair,
+    /// An effect of the region around a lair.
+    Regional,
+    /// Anything else the DM wants to be reminded of.
+    Reminder,
+}
+
+/// Something that happens on an initiative count without being a creature, e.g. a lair action.
+/// It takes its turn after every creature with the same or a higher initiative, so it loses
+/// ties.
+///
+/// * `kind`: what the entry stands for.
+/// * `count`: the initiative count it happens on.
+/// * `description`: what happens, may be empty for lair actions and regional effects.
+/// * `round`: the only round it happens in, every round if `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InitiativeEntry {
+    pub kind: EntryKind,
+    pub count: u8,
+    pub description: String,
+    #[serde(default)]
+    pub round: Option<u32>,
+}
+
+impl InitiativeEntry {
+    pub fn new(kind: EntryKind, count: u8, description: impl Into<String>) -> Self {
+        InitiativeEntry {
+            kind,
+            count,
+            description: description.into(),
+            round: None,
+        }
+    }
+
+    /// Make the entry happen in `round` only.
+    pub fn in_round(mut self, round: u32) -> Self {
+        self.round = Some(round);
+        self
+    }
+
+    /// Returns `true` if the entry happens in `round`.
+    pub fn happens_in(&self, round: u32) -> bool {
+        self.round.is_none_or(|r| r == round)
+    }
+
+    /// Return the name of the entry for the initiative table, e.g. `"Lair action: Tremor"`.
+    pub fn label(&self) -> String {
+        let kind = match self.kind {
+            EntryKind::Lair => "Lair action",
+            EntryKind::Regional => "Regional effect",
+            EntryKind::Reminder => return self.description.clone(),
+        };
+        if self.description.is_empty() {
+            kind.to_string()
+        } else {
+            format!("{}: {}", kind, self.description)
+        }
+    }
+}
+
+impl FromStr for InitiativeEntry {
+    type Err = String;
+
+    /// Parse an entry as `[count] [lair|regional] [round N] [description]`, e.g. `lair`,
+    /// `15 regional Fog rolls in` or `10 round 3 The ceiling collapses`. Lair actions default to
+    /// count 20, reminders need a description.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace().peekable();
+        let count = words
+            .next_if(|w| w.parse::<u8>().is_ok())
+            .and_then(|w| w.parse::<u8>().ok());
+        let kind = match words.peek().map(|w| w.to_lowercase()).as_deref() {
+            Some("lair") => EntryKind::Lair,
+            Some("regional") => EntryKind::Regional,
+            _ => EntryKind::Reminder,
+        };
+        if kind != EntryKind::Reminder {
+            words.next();
+        }
+        let round = match words.next_if(|w| w.eq_ignore_ascii_case("round")) {
+            Some(_) => match words.next().map(str::parse::<u32>) {
+                Some(Ok(round @ 1..)) => Some(round),
+                _ => return Err(String::from("Expected a round number after 'round'")),
+            },
+            None => None,
+        };
+        let description = words.collect::<Vec<_>>().join(" ");
+
+        let count = match (count, kind) {
+            (Some(count), _) => count,
+            (None, EntryKind::Lair) => 20,
+            (None, _) => return Err(String::from("Expected an initiative count first")),
+        };
+        if kind == EntryKind::Reminder && description.is_empty() {
+            return Err(String::from("A reminder needs a description"));
+        }
+        let entry = InitiativeEntry::new(kind, count, description);
+        Ok(match round {
+            Some(round) => entry.in_round(round),
+            None => entry,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntryKind, InitiativeEntry};
+
+    #[test]
+    fn entries_parse_with_defaults() {
+        assert_eq!(
+            "lair".parse(),
+            Ok(InitiativeEntry::new(EntryKind::Lair, 20, ""))
+        );
+        assert_eq!(
+            "15 Regional Fog rolls in".parse(),
+            Ok(InitiativeEntry::new(
+                EntryKind::Regional,
+                15,
+                "Fog rolls in"
+            ))
+        );
+        assert_eq!(
+            "10 round 3 The ceiling collapses".parse(),
+            Ok(InitiativeEntry::new(EntryKind::Reminder, 10, "The ceiling collapses").in_round(3))
+        );
+        assert!("10".parse::<InitiativeEntry>().is_err());
+        assert!("The ceiling collapses".parse::<InitiativeEntry>().is_err());
+        assert!("10 round three Boom".parse::<InitiativeEntry>().is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// A monster ability that is spent when used and comes back on a d6 roll at the start of the
+/// monster's turn, e.g. a dragon's breath weapon with "Recharge 5–6".
+///
+/// * `name`: the name of the ability.
+/// * `recharge`: the lowest roll that recharges it, 5 for "Recharge 5–6".
+/// * `available`: the ability can be used, it is not until it recharges after using it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RechargeAbility {
+    pub name: String,
+    pub recharge: u8,
+    pub available: bool,
+}
+
+impl RechargeAbility {
+    /// An ability recharging on `recharge` or higher, available to use.
+    pub fn new(name: impl Into<String>, recharge: u8) -> Self {
+        RechargeAbility {
+            name: name.into(),
+            recharge: recharge.clamp(1, 6),
+            available: true,
+        }
+    }
+
+    /// Make the ability available again if `roll` is high enough. Returns `true` if it is
+    /// available now.
+    pub fn recharge_with(&mut self, roll: u8) -> bool {
+        if roll >= self.recharge {
+            self.available = true;
+        }
+        self.available
+    }
+
+    /// Return the rolls recharging the ability as written in stat blocks, e.g. `"5–6"` or `"6"`.
+    pub fn range(&self) -> String {
+        if self.recharge >= 6 {
+            String::from("6")
+        } else {
+            format!("{}–6", self.recharge)
+        }
+    }
+
+    /// Parse a comma separated list of abilities, e.g. `"Fire Breath 5-6, Roar 6"`.
+    pub fn parse_list(s: &str) -> Result<Vec<RechargeAbility>, String> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Format `abilities` as a comma separated list, the inverse of `RechargeAbility::parse_list`.
+    pub fn format_list(abilities: &[RechargeAbility]) -> String {
+        abilities
+            .iter()
+            .map(|a| format!("{} {}", a.name, a.range().replace('–', "-")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl FromStr for RechargeAbility {
+    type Err = String;
+
+    /// Parse an ability from its name followed by the rolls recharging it, e.g.
+    /// `Fire Breath 5-6`, `Roar 6` or `Fire Breath (Recharge 5–6)`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Expected e.g. Fire Breath 5-6, not '{}'", s.trim());
+        let s = s.trim().trim_end_matches(')');
+        let (name, range) = s.rsplit_once(' ').ok_or_else(error)?;
+        let low = match range.split_once(['-', '–']) {
+            Some((low, "6")) => low,
+            Some(_) => return Err(error()),
+            None => range,
+        };
+        let recharge = match low.parse::<u8>() {
+            Ok(recharge @ 1..=6) => recharge,
+            _ => return Err(error()),
+        };
+
+        let name = name.trim_end();
+        let name = name.strip_suffix("Recharge").unwrap_or(name);
+        let name = name.trim_end().trim_end_matches('(').trim_end();
+        if name.is_empty() {
+            return Err(error());
+        }
+        Ok(RechargeAbility::new(name, recharge))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RechargeAbility;
+
+    #[test]
+    fn abilities_parse_from_stat_block_notation() {
+        assert_eq!(
+            RechargeAbility::parse_list("Fire Breath (Recharge 5–6), Roar 6"),
+            Ok(vec![
+                RechargeAbility::new("Fire Breath", 5),
+                RechargeAbility::new("Roar", 6)
+            ])
+        );
+        assert_eq!(
+            RechargeAbility::format_list(&RechargeAbility::parse_list("Bite 4-6").unwrap()),
+            "Bite 4-6"
+        );
+        assert!("Fire Breath".parse::<RechargeAbility>().is_err());
+        assert!("Fire Breath 5-7".parse::<RechargeAbility>().is_err());
+        assert!("5-6".parse::<RechargeAbility>().is_err());
+    }
+
+    #[test]
+    fn spent_abilities_recharge_on_high_enough_rolls() {
+        let mut breath = RechargeAbility::new("Fire Breath", 5);
+        breath.available = false;
+        assert!(!breath.recharge_with(4));
+        assert!(breath.recharge_with(5));
+        assert_eq!(breath.range(), "5–6");
+    }
+}
@@ -1,5 +1,90 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
+/// The six ability scores, in stat block order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Ability {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl Ability {
+    pub const ALL: [Ability; 6] = [
+        Ability::Strength,
+        Ability::Dexterity,
+        Ability::Constitution,
+        Ability::Intelligence,
+        Ability::Wisdom,
+        Ability::Charisma,
+    ];
+
+    /// Return the three letter abbreviation used in stat blocks, e.g. `"DEX"`.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Ability::Strength => "STR",
+            Ability::Dexterity => "DEX",
+            Ability::Constitution => "CON",
+            Ability::Intelligence => "INT",
+            Ability::Wisdom => "WIS",
+            Ability::Charisma => "CHA",
+        }
+    }
+
+    /// Return the ability after this one, or `None` for `Charisma`.
+    pub fn next(&self) -> Option<Ability> {
+        Ability::ALL.get(*self as usize + 1).copied()
+    }
+
+    /// Return the ability before this one, or `None` for `Strength`.
+    pub fn previous(&self) -> Option<Ability> {
+        (*self as usize).checked_sub(1).map(|i| Ability::ALL[i])
+    }
+
+    /// Parse a comma separated list of abilities, e.g. `"dex, wis"`.
+    pub fn parse_list(s: &str) -> Result<Vec<Ability>, String> {
+        let mut abilities = Vec::new();
+        for name in s.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let ability = name.parse()?;
+            if !abilities.contains(&ability) {
+                abilities.push(ability);
+            }
+        }
+        abilities.sort_by_key(|a| *a as usize);
+        Ok(abilities)
+    }
+
+    /// Format `abilities` as a comma separated list of abbreviations, the inverse of
+    /// `Ability::parse_list`.
+    pub fn format_list(abilities: &[Ability]) -> String {
+        abilities
+            .iter()
+            .map(Ability::abbreviation)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl FromStr for Ability {
+    type Err = String;
+
+    /// Parse an ability from its abbreviation or full name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Ability::ALL
+            .into_iter()
+            .find(|a| {
+                a.abbreviation().eq_ignore_ascii_case(s)
+                    || format!("{:?}", a).eq_ignore_ascii_case(s)
+            })
+            .ok_or_else(|| format!("Unknown ability '{}'", s))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Stats {
     pub strength: u8,
@@ -72,30 +157,26 @@ impl Stats {
         }
     }
 
-    /// Return the strength modifier
-    pub fn str_mod(&self) -> i8 {
-        Stats::to_mod(self.strength)
-    }
-
     /// Return the dexterity modifier
     pub fn dex_mod(&self) -> i8 {
         Stats::to_mod(self.dexterity)
     }
-    /// Return the constitution modifier
-    pub fn con_mod(&self) -> i8 {
-        Stats::to_mod(self.constitution)
-    }
-    /// Return the intelligence modifier
-    pub fn int_mod(&self) -> i8 {
-        Stats::to_mod(self.intelligence)
-    }
-    /// return the wisdom modifier
-    pub fn wis_mod(&self) -> i8 {
-        Stats::to_mod(self.wisdom)
+
+    /// Return the score of `ability`
+    pub fn score(&self, ability: Ability) -> u8 {
+        match ability {
+            Ability::Strength => self.strength,
+            Ability::Dexterity => self.dexterity,
+            Ability::Constitution => self.constitution,
+            Ability::Intelligence => self.intelligence,
+            Ability::Wisdom => self.wisdom,
+            Ability::Charisma => self.charisma,
+        }
     }
-    /// Return the charisma modifier
-    pub fn cha_mod(&self) -> i8 {
-        Stats::to_mod(self.charisma)
+
+    /// Return the modifier of `ability`
+    pub fn modifier(&self, ability: Ability) -> i8 {
+        Stats::to_mod(self.score(ability))
     }
 }
 impl Default for Stats {
@@ -110,3 +191,46 @@ impl Default for Stats {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Ability, Stats};
+
+    #[test]
+    fn abilities_parse_from_abbreviations_and_names() {
+        assert_eq!("wis".parse(), Ok(Ability::Wisdom));
+        assert_eq!("Constitution".parse(), Ok(Ability::Constitution));
+        assert!("luck".parse::<Ability>().is_err());
+    }
+
+    #[test]
+    fn modifier_matches_named_getters() {
+        let stats = Stats::new(8, 14, 12, 10, 17, 30);
+        assert_eq!(stats.modifier(Ability::Strength), -1);
+        assert_eq!(stats.modifier(Ability::Dexterity), stats.dex_mod());
+        assert_eq!(stats.modifier(Ability::Wisdom), 3);
+        assert_eq!(stats.modifier(Ability::Charisma), 10);
+    }
+
+    #[test]
+    fn ability_lists_parse_in_stat_block_order() {
+        assert_eq!(
+            Ability::parse_list("wis, dex,wis"),
+            Ok(vec![Ability::Dexterity, Ability::Wisdom])
+        );
+        assert_eq!(Ability::parse_list(""), Ok(vec![]));
+        assert!(Ability::parse_list("dex, luck").is_err());
+        assert_eq!(
+            Ability::format_list(&[Ability::Strength, Ability::Constitution]),
+            "STR, CON"
+        );
+    }
+
+    #[test]
+    fn ability_steps_in_stat_block_order() {
+        assert_eq!(Ability::Strength.next(), Some(Ability::Dexterity));
+        assert_eq!(Ability::Charisma.next(), None);
+        assert_eq!(Ability::Strength.previous(), None);
+        assert_eq!(Ability::Charisma.previous(), Some(Ability::Wisdom));
+    }
+}
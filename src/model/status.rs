@@ -1,7 +1,10 @@
-use serde::{Deserialize, Serialize};
+use std::{fmt, mem};
 
-use crate::model::creature::CreatureId;
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+use crate::model::{creature::CreatureId, stats::Ability};
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum Status {
     Blinded,
     Charmed,
@@ -19,3 +22,254 @@ pub enum Status {
     Stunned,
     Unconscious,
 }
+
+impl Status {
+    /// Every condition once, in alphabetical order. `Exhaustion` is at level 1 and `Grappled`
+    /// has no grappler yet.
+    pub const ALL: [Status; 15] = [
+        Status::Blinded,
+        Status::Charmed,
+        Status::Deafened,
+        Status::Exhaustion(1),
+        Status::Frightened,
+        Status::Grappled(Uuid::nil()),
+        Status::Incapacitated,
+        Status::Invisible,
+        Status::Paralyzed,
+        Status::Petrified,
+        Status::Poisoned,
+        Status::Prone,
+        Status::Restrained,
+        Status::Stunned,
+        Status::Unconscious,
+    ];
+
+    /// Returns `true` if `other` is the same condition, whatever its exhaustion level or
+    /// grappler.
+    pub fn same_condition(&self, other: &Status) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+    }
+
+    /// Return a three letter abbreviation of the condition for compact display.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Status::Blinded => "BLI",
+            Status::Charmed => "CHA",
+            Status::Deafened => "DEA",
+            Status::Exhaustion(_) => "EXH",
+            Status::Frightened => "FRI",
+            Status::Grappled(_) => "GRP",
+            Status::Incapacitated => "INC",
+            Status::Invisible => "INV",
+            Status::Paralyzed => "PAR",
+            Status::Petrified => "PET",
+            Status::Poisoned => "POI",
+            Status::Prone => "PRN",
+            Status::Restrained => "RES",
+            Status::Stunned => "STU",
+            Status::Unconscious => "UNC",
+        }
+    }
+
+    /// Return the name of the condition, e.g. `"Exhaustion 2"`.
+    pub fn name(&self) -> String {
+        match self {
+            Status::Blinded => String::from("Blinded"),
+            Status::Charmed => String::from("Charmed"),
+            Status::Deafened => String::from("Deafened"),
+            Status::Exhaustion(level) => format!("Exhaustion {}", level),
+            Status::Frightened => String::from("Frightened"),
+            Status::Grappled(_) => String::from("Grappled"),
+            Status::Incapacitated => String::from("Incapacitated"),
+            Status::Invisible => String::from("Invisible"),
+            Status::Paralyzed => String::from("Paralyzed"),
+            Status::Petrified => String::from("Petrified"),
+            Status::Poisoned => String::from("Poisoned"),
+            Status::Prone => String::from("Prone"),
+            Status::Restrained => String::from("Restrained"),
+            Status::Stunned => String::from("Stunned"),
+            Status::Unconscious => String::from("Unconscious"),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// The start or end of a turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurnBoundary {
+    Start,
+    End,
+}
+
+/// How long a `StatusEffect` lasts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Duration {
+    /// Until it is removed by hand.
+    #[default]
+    Indefinite,
+    /// For this many turns of the affected creature, counted down at the end of each of them.
+    Rounds(u32),
+    /// Until the start or end of the next turn of `creature`, e.g. "until the end of the
+    /// caster's next turn".
+    UntilTurn {
+        creature: CreatureId,
+        boundary: TurnBoundary,
+    },
+}
+
+impl Duration {
+    /// Return the creature whose turn end counts for the duration of an effect on `affected`.
+    pub fn counted_turn(&self, affected: CreatureId) -> Option<CreatureId> {
+        match self {
+            Duration::UntilTurn {
+                creature,
+                boundary: TurnBoundary::End,
+            } => Some(*creature),
+            Duration::Rounds(_) => Some(affected),
+            _ => None,
+        }
+    }
+
+    /// Count the duration of an effect on `affected` down for `boundary` of the turn of the
+    /// creature with id `anchor`, and return `true` if it ran out. A set `skip_turn_end` is used
+    /// up instead of counting the end of the turn.
+    pub fn pass(
+        &mut self,
+        skip_turn_end: &mut bool,
+        affected: CreatureId,
+        anchor: CreatureId,
+        boundary: TurnBoundary,
+    ) -> bool {
+        match *self {
+            Duration::UntilTurn {
+                creature,
+                boundary: ends_at,
+            } if creature == anchor && ends_at == boundary => !mem::take(skip_turn_end),
+            Duration::Rounds(rounds) if affected == anchor && boundary == TurnBoundary::End => {
+                if mem::take(skip_turn_end) {
+                    false
+                } else {
+                    *self = Duration::Rounds(rounds.saturating_sub(1));
+                    rounds <= 1
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A saving throw the affected creature repeats at the end of each of its turns, ending the
+/// effect on a success.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepeatSave {
+    pub dc: u8,
+    pub ability: Ability,
+}
+
+impl fmt::Display for RepeatSave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DC {} {}", self.dc, self.ability.abbreviation())
+    }
+}
+
+/// A `Status` affecting a creature, with what it takes for it to end.
+///
+/// * `status`: the condition itself.
+/// * `duration`: when the effect ends on its own.
+/// * `source`: the creature that caused the effect, if any.
+/// * `save`: the save the affected creature repeats to end the effect early.
+/// * `concentration`: the effect ends when `source` loses concentration.
+/// * `skip_turn_end`: the effect started during the turn whose end would count for `duration`,
+///   so that end is not counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub status: Status,
+    #[serde(default)]
+    pub duration: Duration,
+    #[serde(default)]
+    pub source: Option<CreatureId>,
+    #[serde(default)]
+    pub save: Option<RepeatSave>,
+    #[serde(default)]
+    pub concentration: bool,
+    #[serde(default)]
+    pub skip_turn_end: bool,
+}
+
+impl StatusEffect {
+    /// An effect lasting until it is removed by hand.
+    pub fn new(status: Status) -> Self {
+        StatusEffect {
+            status,
+            duration: Duration::Indefinite,
+            source: None,
+            save: None,
+            concentration: false,
+            skip_turn_end: false,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_save(mut self, dc: u8, ability: Ability) -> Self {
+        self.save = Some(RepeatSave { dc, ability });
+        self
+    }
+
+    /// Keep the effect up by the concentration of `caster`, who becomes its source.
+    pub fn with_concentration(mut self, caster: CreatureId) -> Self {
+        self.source = Some(caster);
+        self.concentration = true;
+        self
+    }
+}
+
+/// Read the status effects of a creature, also accepting the plain list of `Status` older
+/// encounters were stored with.
+pub fn deserialize_effects<'de, D>(deserializer: D) -> Result<Vec<StatusEffect>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Effect(StatusEffect),
+        Plain(Status),
+    }
+
+    Ok(Vec::<Stored>::deserialize(deserializer)?
+        .into_iter()
+        .map(|stored| match stored {
+            Stored::Effect(effect) => effect,
+            Stored::Plain(status) => StatusEffect::new(status),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Duration, Status, StatusEffect, deserialize_effects};
+
+    #[test]
+    fn plain_statuses_deserialize_as_indefinite_effects() {
+        let mut deserializer = serde_json::Deserializer::from_str(
+            r#"["Prone", {"status": "Stunned", "duration": {"Rounds": 1}}]"#,
+        );
+        let effects = deserialize_effects(&mut deserializer).unwrap();
+        assert_eq!(
+            effects,
+            [
+                StatusEffect::new(Status::Prone),
+                StatusEffect::new(Status::Stunned).with_duration(Duration::Rounds(1)),
+            ]
+        );
+    }
+}
@@ -85,6 +85,33 @@ pub fn store_encounter(
     Ok(path)
 }
 
+/// Store the app state to `$XDG_STATE_HOME/intuitive/state.json`
+pub fn store_state(state: &App) -> Result<PathBuf, io::Error> {
+    let data = serde_json::to_string_pretty(state)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let path = xdg_state_home().join("intuitive/state.json");
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&path, data)?;
+    Ok(path)
+}
+
+pub fn load_state() -> Result<Option<App>, io::Error> {
+    let path = xdg_state_home().join("intuitive/state.json");
+
+    if fs::exists(&path)? {
+        let data = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&data).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, e)
+        })?))
+    } else {
+        Ok(None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{EncounterRecord, load_encounter};
@@ -131,30 +158,3 @@ mod tests {
         fs::remove_file(&path).unwrap();
     }
 }
-
-/// Store the app state to `$XDG_STATE_HOME/intuitive/state.json`
-pub fn store_state(state: &App) -> Result<PathBuf, io::Error> {
-    let data = serde_json::to_string_pretty(state)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-    let path = xdg_state_home().join("intuitive/state.json");
-
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    fs::write(&path, data)?;
-    Ok(path)
-}
-
-pub fn load_state() -> Result<Option<App>, io::Error> {
-    let path = xdg_state_home().join("intuitive/state.json");
-
-    if fs::exists(&path)? {
-        let data = fs::read_to_string(path)?;
-        Ok(Some(serde_json::from_str(&data).map_err(|e| {
-            io::Error::new(io::ErrorKind::InvalidData, e)
-        })?))
-    } else {
-        Ok(None)
-    }
-}
@@ -1,23 +1,52 @@
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Layout, Rect},
+    layout::{Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, BorderType, Clear, Padding, Paragraph, Row, Table},
+    widgets::{Block, BorderType, Clear, Padding, Paragraph, Row, Table, Wrap},
 };
 use tui_input::Input;
 
-use crate::app::{App, EditorField, Panel};
+use crate::{
+    app::{App, CreatureKind, EditorField, Panel, Setting, format_cr, format_hp},
+    model::{
+        Ability, Concentration, Creature, CreatureId, Duration, Encounter, EntryKind, Status,
+        StatusEffect, TurnBoundary,
+    },
+};
 
 pub fn draw_ui(frame: &mut Frame, app: &mut App) {
     // Main UI Chunks, header and main space
-    let chunks = Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).split(frame.area());
+    let chunks = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .split(frame.area());
 
-    let title = Paragraph::new(Span::styled(
+    let encounter = &app.current_encounter;
+    let mut header = vec![Span::styled(
         "Intuitive --- Initiative Tracker",
         Style::default().italic().fg(Color::Yellow),
-    ))
-    .block(
+    )];
+    let turn = match encounter
+        .active_entry
+        .and_then(|i| encounter.entries.get(i))
+    {
+        Some(entry) => Some(entry.label()),
+        None => encounter
+            .active_creature()
+            .map(|c| format!("{}'s turn", c.name())),
+    };
+    if let Some(turn) = turn {
+        header.push(Span::from("   "));
+        header.push(
+            Span::from(format!("Round {} — {}", encounter.round, turn))
+                .bold()
+                .white(),
+        );
+    }
+    let title = Paragraph::new(Line::from(header)).block(
         Block::bordered()
             .border_type(BorderType::Rounded)
             .border_style(Style::default().fg(Color::LightCyan)),
@@ -31,28 +60,150 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
         .split(chunks[1]);
 
     // Sidebar
-    let sidebar_placeholder = Paragraph::new("").block(
+    render_sidebar(frame, app, content_chunks[1]);
+
+    // Main table
+    render_initiative_table(frame, app, content_chunks[0]);
+
+    // Status line
+    if let Some(message) = &app.status_message {
+        frame.render_widget(
+            Paragraph::new(Span::from(format!(" {}", message)).light_yellow()),
+            chunks[2],
+        );
+    }
+
+    match app.current_panel {
+        Panel::Editor => render_editor(frame, app),
+        Panel::Prompt => render_prompt(frame, app),
+        Panel::Conditions => render_condition_picker(frame, app),
+        Panel::MassSave => render_mass_save(frame, app),
+        _ => (),
+    }
+}
+
+fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
+    let focused = app.current_panel == Panel::Sidebar;
+
+    let mut lines = vec![Line::from("Settings").bold().underlined()];
+    for (i, setting) in Setting::ALL.iter().enumerate() {
+        let line = Line::from(vec![
+            Span::from(format!("{}: ", setting.label())),
+            Span::from(setting.value(&app.current_encounter.settings)).light_yellow(),
+        ]);
+        lines.push(if focused && i == app.setting_index {
+            line.on_dark_gray()
+        } else {
+            line
+        });
+    }
+
+    let encounter = &app.current_encounter;
+    if let Some(creature) = encounter.creatures.get(encounter.cursor_index) {
+        lines.push(Line::default());
+        lines.push(Line::from(creature.name().to_string()).bold().underlined());
+        lines.push(Line::from(format!(
+            "HP {}/{}  AC {}",
+            format_hp(creature),
+            creature.max_hp(),
+            creature.ac()
+        )));
+        let saves: Vec<String> = creature
+            .save_proficiencies()
+            .iter()
+            .map(|&a| format!("{} {:+}", a.abbreviation(), creature.save_modifier(a)))
+            .collect();
+        lines.push(Line::from(if saves.is_empty() {
+            format!("Proficiency {:+}", creature.proficiency_bonus())
+        } else {
+            format!(
+                "Proficiency {:+}  Saves {}",
+                creature.proficiency_bonus(),
+                saves.join(", ")
+            )
+        }));
+        let turn = creature.turn_resources();
+        let ready = |used: bool| if used { "used" } else { "ready" };
+        lines.push(Line::from(format!(
+            "Reaction {}  Bonus action {}",
+            ready(turn.reaction_used),
+            ready(turn.bonus_action_used)
+        )));
+        for effect in creature.status_effects() {
+            let details = describe_effect(effect, encounter);
+            let mut spans = vec![Span::from(match effect.status {
+                Status::Grappled(id) => format!("Grappled by {}", creature_name(encounter, id)),
+                status => status.name(),
+            })];
+            if !details.is_empty() {
+                spans.push(Span::from(format!(" ({})", details)).light_yellow());
+            }
+            lines.push(Line::from(spans));
+        }
+        if let Some(concentration) = creature.concentration() {
+            let mut spans = vec![Span::from(format!(
+                "Concentrating on {}",
+                concentration.spell
+            ))];
+            if let Some(duration) = describe_duration(concentration.duration, encounter) {
+                spans.push(Span::from(format!(" ({})", duration)).light_yellow());
+            }
+            lines.push(Line::from(spans));
+            if !concentration.targets.is_empty() {
+                let targets: Vec<&str> = concentration
+                    .targets
+                    .iter()
+                    .map(|id| creature_name(encounter, *id))
+                    .collect();
+                lines.push(Line::from(format!("  • Targets: {}", targets.join(", "))).dim());
+            }
+        }
+        for effect in encounter.settings.exhaustion.effects(creature.exhaustion()) {
+            lines.push(Line::from(format!("  • {}", effect)).dim());
+        }
+        for attack in creature.attacks() {
+            lines.push(Line::from(format!("  ⚔ {}", attack.describe())));
+        }
+        for ability in creature.recharge_abilities() {
+            lines.push(Line::from(vec![
+                Span::from(format!("{} (Recharge {}) ", ability.name, ability.range())),
+                if ability.available {
+                    Span::from("ready").light_green()
+                } else {
+                    Span::from("spent").dim()
+                },
+            ]));
+        }
+    }
+
+    let sidebar = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
         Block::bordered()
             .title("─Sidebar")
+            .title_bottom(if focused {
+                key_hints(&[("Space", "Toggle")])
+            } else {
+                Line::default()
+            })
             .border_type(BorderType::Rounded)
-            .border_style(if app.current_panel == Panel::Sidebar {
+            .border_style(if focused {
                 Color::LightYellow
             } else {
                 Color::LightCyan
-            }),
+            })
+            .padding(Padding::symmetric(1, 0)),
     );
 
-    frame.render_widget(sidebar_placeholder, content_chunks[1]);
-
-    // Main table
-    render_initiative_table(frame, app, content_chunks[0]);
-
-    if app.current_panel == Panel::Editor {
-        render_editor(frame, app)
-    }
+    frame.render_widget(sidebar, area);
 }
 
-fn render_input(frame: &mut Frame, input: &Input, name: &str, active: bool, area: Rect) {
+fn render_input(
+    frame: &mut Frame,
+    input: &Input,
+    name: &str,
+    active: bool,
+    error: Option<&str>,
+    area: Rect,
+) {
     // keep 2 for borders and 1 for cursor
     let width = area.width.max(3) - 3;
     let scroll = input.visual_scroll(width as usize);
@@ -61,21 +212,177 @@ fn render_input(frame: &mut Frame, input: &Input, name: &str, active: bool, area
     } else {
         Color::White.into()
     };
+    let mut block = Block::bordered().title(name);
+    if let Some(error) = error {
+        block = block
+            .border_style(Color::LightRed)
+            .title_bottom(Span::from(error).light_red());
+    }
     let input = Paragraph::new(input.value())
         .style(style)
         .scroll((0, scroll as u16))
-        .block(Block::bordered().title(name));
+        .block(block);
     frame.render_widget(input, area);
 }
 
+fn render_prompt(frame: &mut Frame, app: &App) {
+    let Some(prompt) = app.prompts.front() else {
+        return;
+    };
+    let area = centered_rect_fixed_height(40, 3, frame.area());
+    frame.render_widget(Clear, area);
+    render_input(
+        frame,
+        &prompt.input,
+        &prompt.kind.title(&app.current_encounter),
+        true,
+        prompt.error.as_deref(),
+        area,
+    );
+}
+
+fn render_condition_picker(frame: &mut Frame, app: &App) {
+    let encounter = &app.current_encounter;
+    let picker = &app.condition_picker;
+    let Some(creature) = encounter.creature(picker.creature) else {
+        return;
+    };
+
+    let mut lines = Vec::new();
+    for (i, condition) in Status::ALL.iter().enumerate() {
+        let effect = creature
+            .status_effects()
+            .iter()
+            .find(|e| e.status.same_condition(condition));
+        let name = match (effect.map(|e| e.status), condition) {
+            (Some(Status::Grappled(id)), _) => {
+                format!("Grappled by {}", creature_name(encounter, id))
+            }
+            (Some(status), _) => status.name(),
+            (None, Status::Exhaustion(_)) => String::from("Exhaustion"),
+            (None, Status::Grappled(_)) => match picker.grappler {
+                Some(grappler) => format!("Grappled by {}", creature_name(encounter, grappler)),
+                None => String::from("Grappled"),
+            },
+            (None, condition) => condition.name(),
+        };
+
+        let mut spans = vec![
+            Span::from(if effect.is_some() { "[x] " } else { "[ ] " }),
+            Span::from(name),
+        ];
+        if let Some(effect) = effect {
+            let details = describe_effect(effect, encounter);
+            if !details.is_empty() {
+                spans.push(Span::from(format!("  {}", details)).light_yellow());
+            }
+        }
+        let line = Line::from(spans);
+        lines.push(if i == picker.selected {
+            line.on_dark_gray()
+        } else {
+            line
+        });
+    }
+
+    let area = centered_rect_fixed_height(40, Status::ALL.len() as u16 + 2, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(format!("─Conditions of {}", creature.name()))
+                .title_bottom(key_hints(&[
+                    ("Space", "Toggle"),
+                    ("←/→", "Level/Grappler"),
+                    ("d", "Duration"),
+                    ("v", "Save"),
+                    ("L", "Link to Concentration"),
+                    ("Esc", "Close"),
+                ]))
+                .border_type(BorderType::Rounded)
+                .border_style(Color::LightYellow)
+                .padding(Padding::symmetric(1, 0)),
+        ),
+        area,
+    );
+}
+
+fn render_mass_save(frame: &mut Frame, app: &App) {
+    let encounter = &app.current_encounter;
+    let save = &app.mass_save;
+
+    let mut lines = Vec::new();
+    for (i, creature) in encounter.creatures.iter().enumerate() {
+        let selected = save.selected.contains(&creature.id());
+        let mut spans = vec![
+            Span::from(if selected { "[x] " } else { "[ ] " }),
+            Span::from(format!(
+                "{} ({:+})",
+                creature.name(),
+                creature.save_modifier(save.ability)
+            )),
+        ];
+        match save.roll(creature.id()) {
+            Some((total, true)) => {
+                spans.push(Span::from(format!("  {} saves", total)).light_green())
+            }
+            Some((total, false)) => {
+                spans.push(Span::from(format!("  {} fails", total)).light_red())
+            }
+            None => {}
+        }
+        let line = Line::from(spans);
+        let line = if creature.is_dead() { line.dim() } else { line };
+        lines.push(if i == save.cursor {
+            line.on_dark_gray()
+        } else {
+            line
+        });
+    }
+
+    let area = centered_rect_fixed_height(40, encounter.creatures.len() as u16 + 2, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(format!(
+                    "─DC {} {} Save, {} damage on a success",
+                    save.dc,
+                    save.ability.abbreviation(),
+                    if save.half_on_success { "half" } else { "no" }
+                ))
+                .title_bottom(key_hints(&[
+                    ("Space/a", "Select/All"),
+                    ("Enter", "Roll"),
+                    ("d", "Damage"),
+                    ("h", "Half/None"),
+                    ("v", "Save"),
+                    ("Esc", "Close"),
+                ]))
+                .border_type(BorderType::Rounded)
+                .border_style(Color::LightYellow)
+                .padding(Padding::symmetric(1, 0)),
+        ),
+        area,
+    );
+}
+
 fn render_editor(frame: &mut Frame, app: &mut App) {
-    let editor_area = centered_rect_fixed_height(40, 2 + 3 * 4, frame.area());
+    let editor_area = centered_rect_fixed_height(40, 2 + 3 * 12, frame.area());
     let input_chunks = Layout::vertical([
         Constraint::Length(1),
         Constraint::Length(3),
         Constraint::Length(3),
         Constraint::Length(3),
         Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(3),
         Constraint::Min(0),
     ])
     .split(
@@ -87,69 +394,246 @@ fn render_editor(frame: &mut Frame, app: &mut App) {
         .split(editor_area)[1],
     );
 
-    frame.render_widget(Clear::default(), editor_area);
+    frame.render_widget(Clear, editor_area);
     frame.render_widget(
         Block::bordered()
             .border_type(BorderType::Rounded)
             .border_style(Color::LightBlue)
-            .title("Edit Creature"),
+            .title(if app.editor_state.editing.is_some() {
+                "Edit Creature"
+            } else {
+                "New Creature"
+            }),
         editor_area,
     );
+
+    let kind_style = |kind: CreatureKind| {
+        if app.editor_state.kind == kind {
+            Style::new().bold().black().on_light_blue()
+        } else {
+            Style::new().white()
+        }
+    };
+    let kind = Paragraph::new(Line::from(vec![
+        Span::styled(" Player ", kind_style(CreatureKind::Player)),
+        Span::from("  "),
+        Span::styled(" Monster ", kind_style(CreatureKind::Monster)),
+        Span::from("  "),
+        Span::styled(" Important NPC ", kind_style(CreatureKind::ImportantNpc)),
+    ]))
+    .block(Block::bordered().title("Kind").border_style(
+        if app.editor_state.active_input == EditorField::Kind {
+            Color::Yellow
+        } else {
+            Color::White
+        },
+    ));
+    frame.render_widget(kind, input_chunks[1]);
+
     render_input(
         frame,
         &app.editor_state.name_input,
         "Name",
         app.editor_state.active_input == EditorField::Name,
-        input_chunks[1],
+        app.editor_state.error(EditorField::Name),
+        input_chunks[2],
     );
     let hp_chunks = Layout::horizontal([
         Constraint::Fill(1),
         Constraint::Length(3),
         Constraint::Fill(1),
     ])
-    .split(input_chunks[2]);
+    .split(input_chunks[3]);
     render_input(
         frame,
         &app.editor_state.cur_hp_input,
         "Current HP",
         app.editor_state.active_input == EditorField::CurrentHP,
+        app.editor_state.error(EditorField::CurrentHP),
         hp_chunks[0],
     );
     frame.render_widget(Paragraph::new("   \n / \n   ").bold().white(), hp_chunks[1]);
     render_input(
         frame,
         &app.editor_state.max_hp_input,
-        "Max HP",
+        "Max HP / Dice",
         app.editor_state.active_input == EditorField::MaxHP,
+        app.editor_state.error(EditorField::MaxHP),
         hp_chunks[2],
     );
+    let detail_chunks = Layout::horizontal([Constraint::Fill(1); 3]).split(input_chunks[4]);
     render_input(
         frame,
         &app.editor_state.ac_input,
         "AC",
         app.editor_state.active_input == EditorField::AC,
-        input_chunks[3],
+        app.editor_state.error(EditorField::AC),
+        detail_chunks[0],
     );
     render_input(
         frame,
         &app.editor_state.cr_input,
-        "Lvl / CR",
+        app.editor_state.level_or_cr_label(),
         app.editor_state.active_input == EditorField::CR,
-        input_chunks[4],
+        app.editor_state.error(EditorField::CR),
+        detail_chunks[1],
+    );
+    render_input(
+        frame,
+        &app.editor_state.amount_input,
+        "Amount",
+        app.editor_state.active_input == EditorField::Amount,
+        app.editor_state.error(EditorField::Amount),
+        detail_chunks[2],
+    );
+
+    let stat_chunks = Layout::horizontal([Constraint::Fill(1); 6]).split(input_chunks[5]);
+    for ability in Ability::ALL {
+        render_input(
+            frame,
+            &app.editor_state.stat_inputs[ability as usize],
+            ability.abbreviation(),
+            app.editor_state.active_input == EditorField::Stat(ability),
+            app.editor_state.error(EditorField::Stat(ability)),
+            stat_chunks[ability as usize],
+        );
+    }
+
+    let lists = [
+        (
+            EditorField::SaveProficiencies,
+            &app.editor_state.saves_input,
+            "Save Proficiencies",
+        ),
+        (
+            EditorField::Resistances,
+            &app.editor_state.resistances_input,
+            "Resistances",
+        ),
+        (
+            EditorField::Vulnerabilities,
+            &app.editor_state.vulnerabilities_input,
+            "Vulnerabilities",
+        ),
+        (
+            EditorField::Immunities,
+            &app.editor_state.immunities_input,
+            "Immunities",
+        ),
+    ];
+    for (i, (field, input, name)) in lists.into_iter().enumerate() {
+        render_input(
+            frame,
+            input,
+            name,
+            app.editor_state.active_input == field,
+            app.editor_state.error(field),
+            input_chunks[6 + i],
+        );
+    }
+
+    let legendary_chunks = Layout::horizontal([Constraint::Fill(1); 2]).split(input_chunks[10]);
+    let legendary = [
+        (
+            EditorField::LegendaryActions,
+            &app.editor_state.legendary_actions_input,
+            "Legendary Actions",
+        ),
+        (
+            EditorField::LegendaryResistances,
+            &app.editor_state.legendary_resistances_input,
+            "Legendary Resistances",
+        ),
+    ];
+    for (i, (field, input, name)) in legendary.into_iter().enumerate() {
+        render_input(
+            frame,
+            input,
+            name,
+            app.editor_state.active_input == field,
+            app.editor_state.error(field),
+            legendary_chunks[i],
+        );
+    }
+
+    render_input(
+        frame,
+        &app.editor_state.recharge_input,
+        "Recharge (e.g. Fire Breath 5-6)",
+        app.editor_state.active_input == EditorField::Recharge,
+        app.editor_state.error(EditorField::Recharge),
+        input_chunks[11],
+    );
+    render_input(
+        frame,
+        &app.editor_state.attacks_input,
+        "Attacks (e.g. Bite +7 2d6+4 piercing)",
+        app.editor_state.active_input == EditorField::Attacks,
+        app.editor_state.error(EditorField::Attacks),
+        input_chunks[12],
     );
 }
 
 fn render_initiative_table(frame: &mut Frame, app: &mut App, area: Rect) {
-    let header = Row::new(["Name", "Lvl", "HP", "AC", "Initiative"])
+    let header = Row::new(["Name", "Lvl", "HP", "AC", "Initiative", "Conditions"])
         .bold()
         .bottom_margin(1);
 
     app.sync_table_state();
 
+    let encounter = &app.current_encounter;
+    // Initiative entries go right before the creature they take their turn before.
+    let mut entries: Vec<(usize, usize)> = (0..encounter.entries.len())
+        .map(|i| (encounter.entry_slot(i), i))
+        .collect();
+    entries.sort_by_key(|&(slot, i)| (slot, std::cmp::Reverse(encounter.entries[i].count), i));
+    let mut entries = entries.into_iter().peekable();
+    let entry_row = |i: usize| {
+        let entry = &encounter.entries[i];
+        let is_active = encounter.active_entry == Some(i);
+        let icon = if is_active { "󰞇 " } else { "  " };
+        let style = match entry.kind {
+            EntryKind::Lair => Style::new().magenta(),
+            EntryKind::Regional => Style::new().green(),
+            EntryKind::Reminder => Style::new().light_blue(),
+        }
+        .italic();
+        let style = if is_active {
+            style.on_yellow().dark_gray()
+        } else if !entry.happens_in(encounter.round) {
+            style.dim()
+        } else {
+            style
+        };
+        Row::new([
+            format!("{}{}", icon, entry.label()),
+            String::new(),
+            String::new(),
+            String::new(),
+            entry.count.to_string(),
+            entry
+                .round
+                .map(|round| format!("round {}", round))
+                .unwrap_or_default(),
+        ])
+        .style(style)
+    };
+
     let mut rows = Vec::new();
-    for (i, creature) in app.current_encounter.creatures.iter().enumerate() {
-        let is_selected = app.current_encounter.cursor_index == i;
-        let is_initiative = app.current_encounter.initiative_index == i;
+    let mut active_row = None;
+    for (i, creature) in encounter.creatures.iter().enumerate() {
+        while let Some((_, entry)) = entries.next_if(|&(slot, _)| slot == i) {
+            if encounter.active_entry == Some(entry) {
+                active_row = Some(rows.len());
+            }
+            rows.push(entry_row(entry));
+        }
+
+        let is_selected = encounter.cursor_index == i;
+        let is_initiative = encounter.active_index() == Some(i);
+        if is_initiative {
+            active_row = Some(rows.len());
+        }
 
         let (icon, row_style) = match (is_selected, is_initiative) {
             (true, true) => ("󰞇", Style::new().on_yellow().dark_gray()),
@@ -157,39 +641,69 @@ fn render_initiative_table(frame: &mut Frame, app: &mut App, area: Rect) {
             (false, true) => ("󰞇 ", Style::new().on_yellow().dark_gray()),
             (false, false) => ("  ", Style::default()),
         };
+        let row_style = if creature.is_dead() {
+            row_style.crossed_out().dim()
+        } else {
+            row_style
+        };
 
         rows.push(
             Row::new([
-                format!("{}{}", icon, creature.name()),
-                if creature.get_level_or_cr().fract() <= f64::EPSILON {
-                    creature.get_level_or_cr().floor().to_string()
-                } else {
-                    match creature.get_level_or_cr() {
-                        0.25 => String::from("1/4"),
-                        0.5 => String::from("1/2"),
-                        0.75 => String::from("3/4"),
-                        _ => creature.get_level_or_cr().floor().to_string(),
-                    }
+                [Some(format!("{}{}", icon, creature.name()))]
+                    .into_iter()
+                    .chain([
+                        death_save_label(creature),
+                        legendary_label(creature),
+                        turn_label(creature),
+                    ])
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                match creature {
+                    Creature::Player { level, .. } => level.to_string(),
+                    Creature::Monster { cr, .. } => format_cr(*cr),
                 },
-                format!("{}/{}", creature.hp(), creature.max_hp()),
+                format!("{}/{}", format_hp(creature), creature.max_hp()),
                 creature.ac().to_string(),
                 match creature.get_initiative() {
                     Some(i) => i.to_string(),
                     None => String::from("n/a"),
                 },
+                creature
+                    .concentration()
+                    .map(concentration_label)
+                    .into_iter()
+                    .chain(
+                        creature
+                            .status_effects()
+                            .iter()
+                            .map(|e| status_label(e, encounter)),
+                    )
+                    .collect::<Vec<_>>()
+                    .join(" "),
             ])
             .style(row_style),
         )
     }
+    for (_, entry) in entries {
+        if encounter.active_entry == Some(entry) {
+            active_row = Some(rows.len());
+        }
+        rows.push(entry_row(entry));
+    }
+    if active_row.is_some() {
+        app.main_table_state.select(active_row);
+    }
 
     let tab = Table::new(
         rows,
         [
             Constraint::Fill(1),
             Constraint::Length(6),
-            Constraint::Length(6),
+            Constraint::Length(10),
             Constraint::Length(6),
             Constraint::Length(10),
+            Constraint::Fill(1),
         ],
     )
     .header(header)
@@ -197,21 +711,29 @@ fn render_initiative_table(frame: &mut Frame, app: &mut App, area: Rect) {
     .block(
         Block::bordered()
             .title("─Initiative Order")
-            .title_bottom(Line::from(
-                Span::from("─")
-                    + Span::from("k/j").bold().white()
-                    + Span::from("─")
-                    + Span::from("Up/Down").white()
-                    + Span::from("──")
-                    + Span::from("Tab").bold().white()
-                    + Span::from("─")
-                    + Span::from("Swap Panel").white()
-                    + Span::from("──")
-                    + Span::from("n").bold().white()
-                    + Span::from("─")
-                    + Span::from("Add Creature").white()
-                    + Span::from("──"),
-            ))
+            .title_bottom(key_hints(&[
+                ("k/j", "Up/Down"),
+                ("Space/Bksp", "Next/Prev Turn"),
+                ("Tab", "Swap Panel"),
+                ("n", "Add Creature"),
+                ("e", "Edit"),
+                ("s", "Sort"),
+                ("r/R", "Roll All/Monsters"),
+                ("i", "Initiative"),
+                ("K/J", "Move"),
+                ("y", "Duplicate"),
+                ("x", "Remove"),
+                ("d/h/t", "Damage/Heal/Temp HP"),
+                ("c", "Conditions"),
+                ("C", "Concentration"),
+                ("m", "Mass Save"),
+                ("l/L", "Legendary Action/Resistance"),
+                ("g", "Recharge Ability"),
+                ("A", "Attack Selected"),
+                ("u/b", "Reaction/Bonus Action"),
+                ("a/E", "Add/Remove Event"),
+                ("w/o", "Save/Open Encounter"),
+            ]))
             .border_type(BorderType::Rounded)
             .border_style(if app.current_panel == Panel::InitiativeTable {
                 Color::LightYellow
@@ -224,22 +746,115 @@ fn render_initiative_table(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_stateful_widget(tab, area, &mut app.main_table_state);
 }
 
-/// Return a centered `Rect` area.
-fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
-    let popup_layout = Layout::vertical([
-        Constraint::Percentage((100 - percent_y) / 2),
-        Constraint::Percentage(percent_y),
-        Constraint::Percentage((100 - percent_y) / 2),
-    ])
-    .split(area);
+/// Label the death saves of a downed player, e.g. `[✓2 ✗1]` or `[stable]`.
+fn death_save_label(creature: &Creature) -> Option<String> {
+    let saves = creature.death_saves().filter(|_| creature.is_downed())?;
+    Some(if saves.stable {
+        String::from("[stable]")
+    } else {
+        format!("[✓{} ✗{}]", saves.successes, saves.failures)
+    })
+}
 
-    Layout::horizontal([
-        Constraint::Percentage((100 - percent_x) / 2),
-        Constraint::Percentage(percent_x),
-        Constraint::Percentage((100 - percent_x) / 2),
-    ])
-    .split(popup_layout[1])[1]
+/// Label the legendary actions and resistances a monster has left, e.g. `[LA 2/3 LR 1/3]`.
+fn legendary_label(creature: &Creature) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(actions) = creature.legendary_actions() {
+        parts.push(format!("LA {}/{}", actions.left, actions.max));
+    }
+    if let Some(resistances) = creature.legendary_resistances() {
+        parts.push(format!("LR {}/{}", resistances.left, resistances.max));
+    }
+    (!parts.is_empty()).then(|| format!("[{}]", parts.join(" ")))
+}
+
+/// Mark the reaction and bonus action a creature has taken, e.g. `[R✗ B✗]`.
+fn turn_label(creature: &Creature) -> Option<String> {
+    let turn = creature.turn_resources();
+    let parts: Vec<&str> = [
+        turn.reaction_used.then_some("R✗"),
+        turn.bonus_action_used.then_some("B✗"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!parts.is_empty()).then(|| format!("[{}]", parts.join(" ")))
 }
+
+/// Return the name of the creature with `id`, or `?` if it is not in the encounter.
+fn creature_name(encounter: &Encounter, id: CreatureId) -> &str {
+    encounter.creature(id).map_or("?", Creature::name)
+}
+
+/// Abbreviate a status for the initiative table, e.g. `EXH2`, `GRP:Ogre` or `STU(3)` with 3
+/// rounds left.
+fn status_label(effect: &StatusEffect, encounter: &Encounter) -> String {
+    let label = match effect.status {
+        Status::Exhaustion(level) => format!("EXH{}", level),
+        Status::Grappled(id) => format!("GRP:{}", creature_name(encounter, id)),
+        status => status.abbreviation().to_string(),
+    };
+    match effect.duration {
+        Duration::Rounds(rounds) => format!("{}({})", label, rounds),
+        _ => label,
+    }
+}
+
+/// Abbreviate a concentration for the initiative table, e.g. `CONC:Bless(3)` with 3 rounds left.
+fn concentration_label(concentration: &Concentration) -> String {
+    match concentration.duration {
+        Duration::Rounds(rounds) => format!("CONC:{}({})", concentration.spell, rounds),
+        _ => format!("CONC:{}", concentration.spell),
+    }
+}
+
+/// Describe how long a status lasts, e.g. `3 rounds`, or `None` if it lasts until removed.
+fn describe_duration(duration: Duration, encounter: &Encounter) -> Option<String> {
+    match duration {
+        Duration::Indefinite => None,
+        Duration::Rounds(1) => Some(String::from("1 round")),
+        Duration::Rounds(rounds) => Some(format!("{} rounds", rounds)),
+        Duration::UntilTurn { creature, boundary } => Some(format!(
+            "until {} of {}'s turn",
+            match boundary {
+                TurnBoundary::Start => "start",
+                TurnBoundary::End => "end",
+            },
+            creature_name(encounter, creature)
+        )),
+    }
+}
+
+/// Describe how long, until which save and whose concentration a status lasts, e.g.
+/// `3 rounds, DC 13 WIS`.
+fn describe_effect(effect: &StatusEffect, encounter: &Encounter) -> String {
+    let mut parts: Vec<String> = describe_duration(effect.duration, encounter)
+        .into_iter()
+        .collect();
+    if let Some(save) = effect.save {
+        parts.push(save.to_string());
+    }
+    if let Some(caster) = effect.source.filter(|_| effect.concentration) {
+        parts.push(format!(
+            "concentration of {}",
+            creature_name(encounter, caster)
+        ));
+    }
+    parts.join(", ")
+}
+
+/// Return a border title listing `(key, action)` pairs, e.g. `─n─Add Creature──`.
+fn key_hints<'a>(hints: &[(&'a str, &'a str)]) -> Line<'a> {
+    let mut spans = vec![Span::from("─")];
+    for (key, action) in hints {
+        spans.push(Span::from(*key).bold().white());
+        spans.push(Span::from("─"));
+        spans.push(Span::from(*action).white());
+        spans.push(Span::from("──"));
+    }
+    Line::from(spans)
+}
+
 /// Return a centered `Rect` area with width as a percentage and height in lines.
 fn centered_rect_fixed_height(percent_x: u16, height: u16, area: Rect) -> Rect {
     let popup_layout = Layout::vertical([